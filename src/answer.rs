@@ -0,0 +1,108 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::grid::Grid;
+
+/// The answer to one part of a puzzle.
+///
+/// Each day's parts return whatever type suits them, which is converted into an `Answer` when the
+/// part is run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(BigInt),
+    Text(String),
+    /// A picture of lit and unlit cells, which usually spells out some letters.
+    Grid(Grid<bool>),
+}
+
+impl Answer {
+    /// Returns whether the answer can only be read by a person, rather than submitted as is.
+    pub fn is_picture(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(value: i32) -> Answer {
+        Answer::Integer(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Answer {
+        Answer::Integer(value)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Answer {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => Answer::BigInteger(BigInt::from(value)),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Answer {
+        Answer::from(value as u64)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Answer {
+        Answer::BigInteger(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<Grid<bool>> for Answer {
+    fn from(value: Grid<bool>) -> Answer {
+        Answer::Grid(value)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+            Answer::Grid(grid) => write!(f, "{}", grid.render(|lit| if *lit { '#' } else { '.' })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Answer::Integer(-3), Answer::from(-3));
+        assert_eq!(Answer::Integer(7), Answer::from(7u64));
+        assert_eq!(
+            Answer::BigInteger(BigInt::from(u64::MAX)),
+            Answer::from(u64::MAX)
+        );
+        assert!(!Answer::from("AB".to_string()).is_picture());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("18446744073709551615", Answer::from(u64::MAX).to_string());
+
+        let grid = Grid::from_rows(vec![vec![true, false], vec![false, true]]).unwrap();
+        let answer = Answer::from(grid);
+        assert!(answer.is_picture());
+        assert_eq!("#.\n.#", answer.to_string());
+    }
+}
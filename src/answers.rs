@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+/// An answer that a day's part is known to produce for a given input file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpectedAnswer {
+    pub day: String,
+    pub part: String,
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+impl ExpectedAnswer {
+    fn from_str(line: &str) -> Option<ExpectedAnswer> {
+        let parts: Vec<&str> = line.splitn(4, ' ').collect();
+        if parts.len() != 4 {
+            return None;
+        }
+
+        Some(ExpectedAnswer {
+            day: parts[0].to_string(),
+            part: parts[1].to_string(),
+            input: PathBuf::from(parts[2]),
+            answer: parts[3].replace("\\n", "\n"),
+        })
+    }
+}
+
+/// Reads a list of expected answers, skipping blank lines and lines starting with `#`.
+pub fn read_answers(contents: &str) -> Result<Vec<ExpectedAnswer>, String> {
+    let mut answers = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match ExpectedAnswer::from_str(line) {
+            Some(answer) => answers.push(answer),
+            None => return Err(format!("line {}: malformed answer: {}", i + 1, line)),
+        }
+    }
+
+    Ok(answers)
+}
@@ -1,40 +1,66 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let entries = read_input();
-    let answer = get_num_1_4_7_8(&entries);
+use crate::error::{ParseError, SourceLine};
+use crate::parsing::match_pattern;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 8;
+
+    type Input = Vec<Entry>;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-pub fn part_two() {
-    let entries = read_input();
-    let answer = solve_2(&entries);
+    fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(entries: &Vec<Entry>) -> i32 {
+        get_num_1_4_7_8(entries)
+    }
 
-    println!("{}", answer);
+    fn part_two(entries: &Vec<Entry>) -> i32 {
+        solve_2(entries)
+    }
 }
 
-fn read_input() -> Vec<(Vec<String>, Vec<String>)> {
-    let stdin = io::stdin();
+type Entry = (Vec<String>, Vec<String>);
 
-    let mut entries: Vec<(Vec<String>, Vec<String>)> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split(" | ").collect();
+fn read_input(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for line in SourceLine::all(input) {
+        let parts = match_pattern(
+            &line,
+            "{} | {}",
+            "expected signal patterns and an output value",
+        )?;
 
-        let signal_patterns: Vec<String> = parts[0].split(' ').map(|s| s.to_string()).collect();
-        let output_value: Vec<String> = parts[1].split(' ').map(|s| s.to_string()).collect();
+        let signal_patterns = read_patterns(&line, parts[0])?;
+        let output_value = read_patterns(&line, parts[1])?;
 
         entries.push((signal_patterns, output_value));
     }
 
-    entries
+    Ok(entries)
+}
+
+fn read_patterns(line: &SourceLine, patterns_str: &str) -> Result<Vec<String>, ParseError> {
+    patterns_str
+        .split(' ')
+        .map(|pattern| {
+            if pattern.is_empty() || !pattern.chars().all(|c| ('a'..='g').contains(&c)) {
+                Err(line.error(pattern, "expected a pattern of segments a-g"))
+            } else {
+                Ok(pattern.to_string())
+            }
+        })
+        .collect()
 }
 
-fn get_num_1_4_7_8(entries: &[(Vec<String>, Vec<String>)]) -> i32 {
+fn get_num_1_4_7_8(entries: &[Entry]) -> i32 {
     let interesting_digit_lengths: Vec<i32> = vec![2, 3, 4, 7];
 
     let mut count = 0;
@@ -53,7 +79,7 @@ fn vec_to_set(vec: &[char]) -> HashSet<char> {
     vec.iter().copied().collect()
 }
 
-fn find_wire_segment_connections(entry: &(Vec<String>, Vec<String>)) -> HashMap<char, char> {
+fn find_wire_segment_connections(entry: &Entry) -> HashMap<char, char> {
     let letters: Vec<char> = vec!['a', 'b', 'c', 'd', 'e', 'f', 'g'];
 
     // Enumerate all the possible connections, so we can later narrow them down
@@ -155,7 +181,7 @@ fn solve_csp(
         .filter(|(_, v)| v.len() > 1)
         .collect();
 
-    unconstrained.sort_by(|(_, v1), (_, v2)| v1.len().cmp(&v2.len()));
+    unconstrained.sort_by_key(|(_, v)| v.len());
 
     let already_matched: Vec<char> = connection_possibilities
         .iter()
@@ -183,7 +209,7 @@ fn map_wires(output_value: &str, mappings: &HashMap<char, char>) -> String {
     output_value.chars().map(|c| mappings[&c]).collect()
 }
 
-fn solve_2(entries: &[(Vec<String>, Vec<String>)]) -> i32 {
+fn solve_2(entries: &[Entry]) -> i32 {
     let mut digit_mapping: HashMap<Vec<char>, i32> = HashMap::new();
     digit_mapping.insert(vec!['a', 'b', 'c', 'e', 'f', 'g'], 0);
     digit_mapping.insert(vec!['c', 'f'], 1);
@@ -203,7 +229,7 @@ fn solve_2(entries: &[(Vec<String>, Vec<String>)]) -> i32 {
         let (_, output_values) = entry;
         let mut num = 0;
         for output_value in output_values.iter() {
-            let output_value = map_wires(&output_value, &connections);
+            let output_value = map_wires(output_value, &connections);
             let mut segments: Vec<char> = output_value.chars().collect();
             segments.sort_unstable();
 
@@ -217,3 +243,36 @@ fn solve_2(entries: &[(Vec<String>, Vec<String>)]) -> i32 {
 
     sum
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/8_example.txt")).unwrap();
+
+        assert_eq!(26, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/8_example.txt")).unwrap();
+
+        assert_eq!(61229, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn part_one_example_small() {
+        let input = Solver::parse(include_str!("../inputs/8_example_small.txt")).unwrap();
+
+        assert_eq!(0, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_small() {
+        let input = Solver::parse(include_str!("../inputs/8_example_small.txt")).unwrap();
+
+        assert_eq!(5353, Solver::part_two(&input));
+    }
+}
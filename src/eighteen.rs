@@ -1,19 +1,31 @@
 use std::fmt;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = solve_1(numbers);
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-    println!("{}", answer);
-}
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 18;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = solve_2(numbers);
+    type Input = Vec<Pair>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Vec<Pair>, ParseError> {
+        read_input(input)
+    }
 
-    println!("{}", answer);
+    fn part_one(pairs: &Vec<Pair>) -> i32 {
+        solve_1(pairs)
+    }
+
+    fn part_two(pairs: &Vec<Pair>) -> i32 {
+        solve_2(pairs)
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -78,7 +90,7 @@ impl Path {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-enum Pair {
+pub enum Pair {
     Element(i32),
     Pair(Box<Pair>, Box<Pair>),
 }
@@ -332,90 +344,64 @@ impl Pair {
         }
     }
 
-    fn from_str(pair_str: &str) -> Pair {
-        let mut stack: Vec<Result<Pair, Vec<Pair>>> = vec![];
-        let mut i = 0;
-        while i < pair_str.len() {
-            if pair_str.chars().nth(i).unwrap() == '[' {
-                stack.push(Err(vec![]));
-
-                i += 1;
-            } else if pair_str.chars().nth(i).unwrap() == ']' {
-                let elements = stack.pop().unwrap();
-                match elements {
-                    Err(elements) => {
-                        let new_pair = Pair::Pair(
-                            Box::new(elements[0].clone()),
-                            Box::new(elements[1].clone()),
-                        );
-
-                        if stack.last().map(|p| p.is_err()).unwrap_or(false) {
-                            match stack.last_mut().unwrap() {
-                                Err(ps) => ps.push(new_pair),
-                                _ => panic!(),
-                            }
-                        } else {
-                            stack.push(Ok(new_pair));
-                        }
-                    }
-                    Ok(pair) => {
-                        let elements = stack.pop().unwrap().err().unwrap();
-
-                        let new_pair = Pair::Pair(Box::new(elements[0].clone()), Box::new(pair));
-
-                        if stack.last().map(|p| p.is_err()).unwrap_or(false) {
-                            match stack.last_mut().unwrap() {
-                                Err(ps) => ps.push(new_pair),
-                                _ => panic!(),
-                            }
-                        } else {
-                            stack.push(Ok(new_pair));
-                        }
-                    }
-                }
+    fn from_str(line: &SourceLine) -> Result<Pair, ParseError> {
+        let (pair, end) = Pair::from_str_at(line, 0)?;
+
+        if end < line.text.len() {
+            return Err(line.error(&line.text[end..], "unexpected text after snailfish number"));
+        }
+
+        Ok(pair)
+    }
+
+    fn from_str_at(line: &SourceLine, start: usize) -> Result<(Pair, usize), ParseError> {
+        let rest = &line.text[start..];
+
+        match rest.chars().next() {
+            Some('[') => {
+                let (left, i) = Pair::from_str_at(line, start + 1)?;
+                let i = Pair::expect_char(line, i, ',')?;
+                let (right, i) = Pair::from_str_at(line, i)?;
+                let i = Pair::expect_char(line, i, ']')?;
+
+                Ok((Pair::Pair(Box::new(left), Box::new(right)), i))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let value: i32 = line.parse(&rest[..len], "expected a regular number")?;
 
-                i += 1;
-            } else if pair_str.chars().nth(i).unwrap() == ',' {
-                i += 1;
-            } else {
-                let end_i = pair_str
-                    .chars()
-                    .enumerate()
-                    .skip(i)
-                    .find(|(_, c)| vec![',', ']'].contains(c))
-                    .unwrap()
-                    .0;
-
-                let part_str = pair_str[i..end_i].to_string();
-                let pair = Pair::Element(part_str.parse().unwrap());
-
-                match stack.last_mut().unwrap() {
-                    Err(p) => p.push(pair),
-                    _ => panic!(),
-                };
-
-                i = end_i;
+                Ok((Pair::Element(value), start + len))
             }
+            Some(_) => Err(line.error_char(start, "expected a pair or a regular number")),
+            None => Err(line.error(rest, "expected a pair or a regular number")),
         }
+    }
 
-        stack.pop().unwrap().unwrap()
+    fn expect_char(line: &SourceLine, i: usize, expected: char) -> Result<usize, ParseError> {
+        match line.text[i..].chars().next() {
+            Some(c) if c == expected => Ok(i + 1),
+            Some(_) => Err(line.error_char(i, &format!("expected '{}'", expected))),
+            None => Err(line.error(&line.text[i..], &format!("expected '{}'", expected))),
+        }
     }
 }
 
-fn read_input() -> Vec<Pair> {
-    let stdin = io::stdin();
-
+fn read_input(input: &str) -> Result<Vec<Pair>, ParseError> {
     let mut pairs: Vec<Pair> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
+    for line in SourceLine::all(input) {
+        pairs.push(Pair::from_str(&line)?);
+    }
 
-        pairs.push(Pair::from_str(&line));
+    if pairs.is_empty() {
+        return Err(missing_line(1, "expected a snailfish number"));
     }
 
-    pairs
+    Ok(pairs)
 }
 
-fn solve_1(pairs: Vec<Pair>) -> i32 {
+fn sum(pairs: &[Pair]) -> Pair {
     let mut pair = pairs[0].clone();
     pair.reduce();
 
@@ -423,24 +409,142 @@ fn solve_1(pairs: Vec<Pair>) -> i32 {
         pair = pair.add(p);
     }
 
-    pair.magnitude()
+    pair
+}
+
+fn solve_1(pairs: &[Pair]) -> i32 {
+    sum(pairs).magnitude()
+}
+
+fn get_max_sum_magnitude(p1: &Pair, pairs: &[Pair]) -> i32 {
+    pairs
+        .iter()
+        .filter(|p2| p1 != *p2)
+        .map(|p2| p1.add(p2).magnitude())
+        .max()
+        .unwrap_or(0)
 }
 
-fn solve_2(pairs: Vec<Pair>) -> i32 {
-    let mut max_magnitude = 0;
+fn solve_2(pairs: &[Pair]) -> i32 {
+    #[cfg(feature = "parallel")]
+    let firsts = pairs.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let firsts = pairs.iter();
 
-    for p1 in pairs.iter() {
-        for p2 in pairs.iter() {
-            if p1 != p2 {
-                let sum = p1.add(p2);
-                let magnitude = sum.magnitude();
+    firsts
+        .map(|p1| get_max_sum_magnitude(p1, pairs))
+        .max()
+        .unwrap_or(0)
+}
 
-                if magnitude > max_magnitude {
-                    max_magnitude = magnitude;
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/18_example.txt")).unwrap();
+
+        assert_eq!(4230, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/18_example.txt")).unwrap();
+
+        assert_eq!(4647, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn part_one_example_2() {
+        let input = Solver::parse(include_str!("../inputs/18_example_2.txt")).unwrap();
+
+        assert_eq!(445, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_2() {
+        let input = Solver::parse(include_str!("../inputs/18_example_2.txt")).unwrap();
+
+        assert_eq!(90, Solver::part_two(&input));
+    }
+
+    fn reduced(input: &str) -> String {
+        sum(&Solver::parse(input).unwrap()).to_string()
+    }
+
+    #[test]
+    fn explode_examples() {
+        assert_eq!(
+            "[[[[0,9],2],3],4]",
+            reduced(include_str!("../inputs/18_example_3.txt"))
+        );
+        assert_eq!(
+            "[7,[6,[5,[7,0]]]]",
+            reduced(include_str!("../inputs/18_example_4.txt"))
+        );
+        assert_eq!(
+            "[[6,[5,[7,0]]],3]",
+            reduced(include_str!("../inputs/18_example_5.txt"))
+        );
+        assert_eq!(
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            reduced(include_str!("../inputs/18_example_6.txt"))
+        );
+    }
+
+    #[test]
+    fn sum_examples() {
+        assert_eq!(
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            reduced(include_str!("../inputs/18_example_7.txt"))
+        );
+        assert_eq!(
+            "[[[[1,1],[2,2]],[3,3]],[4,4]]",
+            reduced(include_str!("../inputs/18_example_2.txt"))
+        );
+        assert_eq!(
+            "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+            reduced(include_str!("../inputs/18_example_8.txt"))
+        );
+        assert_eq!(
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]",
+            reduced(include_str!("../inputs/18_example_9.txt"))
+        );
+    }
+
+    #[test]
+    fn part_one_example_8() {
+        let input = Solver::parse(include_str!("../inputs/18_example_8.txt")).unwrap();
+
+        assert_eq!(3488, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_8() {
+        let input = Solver::parse(include_str!("../inputs/18_example_8.txt")).unwrap();
+
+        assert_eq!(3805, Solver::part_two(&input));
     }
 
-    max_magnitude
+    #[test]
+    fn part_one_example_9() {
+        let input = Solver::parse(include_str!("../inputs/18_example_9.txt")).unwrap();
+
+        assert_eq!(4140, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_9() {
+        let input = Solver::parse(include_str!("../inputs/18_example_9.txt")).unwrap();
+
+        assert_eq!(3993, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = Solver::parse("[[1,2],3]\n[1,[2 3]]").err().unwrap();
+
+        assert_eq!((2, 6), (err.line, err.column));
+    }
 }
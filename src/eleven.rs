@@ -1,52 +1,115 @@
 use std::collections::HashSet;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let mut field = read_input();
-    let answer = calc_num_flashes(&mut field, 100);
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::grid::{Grid, Position};
+use crate::params::Params;
+use crate::solution::Solution;
+use crate::visualize::{gradient, Frames, Rgb, WHITE};
 
-    println!("{}", answer);
-}
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 11;
+    const PARAMS: &'static [&'static str] = &["steps"];
+
+    type Input = Field;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Field, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(field: &Field) -> i32 {
+        Self::part_one_with(field, &Params::default()).unwrap()
+    }
+
+    fn part_two(field: &Field) -> i32 {
+        let mut field = field.clone();
+
+        calc_first_all_flash(&mut field)
+    }
+
+    fn part_one_with(field: &Field, params: &Params) -> Result<i32, String> {
+        let mut field = field.clone();
 
-pub fn part_two() {
-    let mut field = read_input();
-    let answer = calc_first_all_flash(&mut field);
+        Ok(calc_num_flashes(&mut field, params.get("steps", 100)?))
+    }
 
-    println!("{}", answer);
+    fn visualize(
+        field: &Field,
+        part: &str,
+        params: &Params,
+        frames: &mut Frames,
+    ) -> Result<(), String> {
+        let steps: u32 = params.get("steps", 100)?;
+        let mut field = field.clone();
+        frames.write(&field.octopi, energy_color)?;
+
+        let mut step = 0;
+        loop {
+            let num_flashes = field.step();
+            step += 1;
+            frames.write(&field.octopi, energy_color)?;
+
+            let done = match part {
+                "one" => step >= steps,
+                _ => num_flashes == 100,
+            };
+            if done {
+                return Ok(());
+            }
+        }
+    }
 }
 
-struct Field {
-    octopi: [[i32; 10]; 10],
+/// Draws octopi that just flashed in white, and the rest brighter the more energy they have.
+fn energy_color(energy: &i32) -> Rgb {
+    if *energy == 0 {
+        WHITE
+    } else {
+        gradient([10, 10, 60], [60, 160, 220], *energy as f64 / 9.0)
+    }
 }
 
-impl Field {
-    fn from_lines(lines: &[String]) -> Field {
-        let mut octopi = [[0; 10]; 10];
+#[derive(Clone)]
+pub struct Field {
+    octopi: Grid<i32>,
+}
 
-        for (i, line) in lines.iter().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                octopi[i][j] = c.to_string().parse().unwrap();
-            }
+impl Field {
+    fn from_lines(lines: &[SourceLine]) -> Result<Field, ParseError> {
+        let octopi = Grid::from_lines(lines, "expected an energy level", |c| {
+            c.to_digit(10).map(|energy| energy as i32)
+        })?;
+
+        let message = "expected a 10x10 grid of energy levels";
+        if octopi.width() != 10 {
+            return Err(lines[0].error_line(message));
+        }
+        if octopi.height() > 10 {
+            return Err(lines[10].error_line(message));
+        }
+        if octopi.height() < 10 {
+            return Err(missing_line(lines.len() + 1, message));
         }
 
-        Field { octopi }
+        Ok(Field { octopi })
     }
 
-    fn flash(&mut self, flashed: &mut HashSet<(usize, usize)>, i: usize, j: usize) -> i32 {
+    fn flash(&mut self, flashed: &mut HashSet<Position>, (x, y): Position) -> i32 {
         let mut num_flashes = 1;
 
-        flashed.insert((i, j));
-
-        for (i2, j2) in self.get_neighbors(i, j) {
-            let value = self.octopi[i2][j2];
+        flashed.insert((x, y));
 
-            if !flashed.contains(&(i2, j2)) {
-                if value >= 9 {
-                    self.octopi[i2][j2] = 0;
-                    num_flashes += self.flash(flashed, i2, j2);
+        let neighbors: Vec<Position> = self.octopi.all_neighbors(x, y).collect();
+        for neighbor in neighbors {
+            if !flashed.contains(&neighbor) {
+                if self.octopi[neighbor] >= 9 {
+                    self.octopi[neighbor] = 0;
+                    num_flashes += self.flash(flashed, neighbor);
                 } else {
-                    self.octopi[i2][j2] += 1;
+                    self.octopi[neighbor] += 1;
                 }
             }
         }
@@ -57,18 +120,15 @@ impl Field {
     fn step(&mut self) -> i32 {
         let mut num_flashes = 0;
 
-        let mut flashed: HashSet<(usize, usize)> = HashSet::new();
-        for i in 0..self.octopi.len() {
-            for j in 0..self.octopi[0].len() {
-                let value = self.octopi[i][j];
-
-                if !flashed.contains(&(i, j)) {
-                    if value >= 9 {
-                        self.octopi[i][j] = 0;
-                        num_flashes += self.flash(&mut flashed, i, j);
-                    } else {
-                        self.octopi[i][j] += 1;
-                    }
+        let mut flashed: HashSet<Position> = HashSet::new();
+        let positions: Vec<Position> = self.octopi.positions().collect();
+        for pos in positions {
+            if !flashed.contains(&pos) {
+                if self.octopi[pos] >= 9 {
+                    self.octopi[pos] = 0;
+                    num_flashes += self.flash(&mut flashed, pos);
+                } else {
+                    self.octopi[pos] += 1;
                 }
             }
         }
@@ -76,42 +136,10 @@ impl Field {
         num_flashes
     }
 
-    fn get_neighbors(&self, i: usize, j: usize) -> Vec<(usize, usize)> {
-        let i = i as i32;
-        let j = j as i32;
-
-        let mut neighbors: Vec<(usize, usize)> = vec![];
-        for i_delta in -1..2 {
-            for j_delta in -1..2 {
-                let n_i = i + i_delta;
-                let n_j = j + j_delta;
-
-                if i_delta == 0 && j_delta == 0 {
-                    continue;
-                }
-
-                if n_i >= 0
-                    && n_i < self.octopi.len() as i32
-                    && n_j >= 0
-                    && n_j < self.octopi[0].len() as i32
-                {
-                    neighbors.push((n_i as usize, n_j as usize));
-                }
-            }
-        }
-
-        neighbors
-    }
-
-    /*fn print(&self) {
-        for i in 0..self.octopi.len() {
-            for j in 0..self.octopi[0].len() {
-                print!("{}", self.octopi[i][j]);
-            }
-            println!();
-        }
+    fn _print(&self) {
+        println!("{}", self.octopi);
         println!("=================================");
-    }*/
+    }
 }
 
 fn calc_num_flashes(field: &mut Field, steps: u32) -> i32 {
@@ -130,14 +158,27 @@ fn calc_first_all_flash(field: &mut Field) -> i32 {
     }
 }
 
-fn read_input() -> Field {
-    let stdin = io::stdin();
-
-    let lines: Vec<String> = stdin
-        .lock()
-        .lines()
-        .map(|l| l.unwrap().to_string())
-        .collect();
+fn read_input(input: &str) -> Result<Field, ParseError> {
+    let lines: Vec<SourceLine> = SourceLine::all(input).collect();
 
     Field::from_lines(&lines)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/11_example.txt")).unwrap();
+
+        assert_eq!(1656, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/11_example.txt")).unwrap();
+
+        assert_eq!(195, Solver::part_two(&input));
+    }
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error found while parsing a day's puzzle input, along with where in the input it occurred.
+///
+/// Lines and columns are counted from 1. The day is filled in by the runner once the error has
+/// made its way out of the day's parser.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: &str) -> ParseError {
+        ParseError {
+            day: None,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn with_day(mut self, day: u32) -> ParseError {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+
+        write!(
+            f,
+            "line {}, column {}: {}: {:?}",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A single line of puzzle input, used to report errors against the parts of it being parsed.
+#[derive(Clone, Copy, Debug)]
+pub struct SourceLine<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> SourceLine<'a> {
+    /// Splits the given input into lines, numbered from 1.
+    pub fn all(input: &'a str) -> impl Iterator<Item = SourceLine<'a>> {
+        input.lines().enumerate().map(|(i, text)| SourceLine {
+            number: i + 1,
+            text,
+        })
+    }
+
+    /// Creates an error for `part`, which should be a slice of this line so that its column can be
+    /// found.
+    pub fn error(&self, part: &str, message: &str) -> ParseError {
+        ParseError::new(self.number, self.column_of(part), part, message)
+    }
+
+    /// Creates an error covering the whole line.
+    pub fn error_line(&self, message: &str) -> ParseError {
+        self.error(self.text, message)
+    }
+
+    /// Creates an error for the character starting at byte `index` of this line.
+    pub fn error_char(&self, index: usize, message: &str) -> ParseError {
+        let len = self.text[index..]
+            .chars()
+            .next()
+            .map(|c| c.len_utf8())
+            .unwrap_or(0);
+
+        self.error(&self.text[index..index + len], message)
+    }
+
+    /// Parses `part`, which should be a slice of this line, reporting `message` if it is invalid.
+    pub fn parse<T: FromStr>(&self, part: &str, message: &str) -> Result<T, ParseError> {
+        part.trim().parse().map_err(|_| self.error(part, message))
+    }
+
+    fn column_of(&self, part: &str) -> usize {
+        let line_start = self.text.as_ptr() as usize;
+        let part_start = part.as_ptr() as usize;
+
+        if part_start >= line_start && part_start <= line_start + self.text.len() {
+            self.text[..part_start - line_start].chars().count() + 1
+        } else {
+            self.text
+                .find(part)
+                .map(|i| self.text[..i].chars().count() + 1)
+                .unwrap_or(1)
+        }
+    }
+}
+
+/// Creates an error for input that ends before an expected line.
+pub fn missing_line(line: usize, message: &str) -> ParseError {
+    ParseError::new(line, 1, "", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_with_day() {
+        let line = SourceLine {
+            number: 3,
+            text: "1,2 -> x,4",
+        };
+        let err = line
+            .error(&line.text[7..8], "expected a number")
+            .with_day(5);
+
+        assert_eq!(
+            "day 5, line 3, column 8: expected a number: \"x\"",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn error_char_counts_columns_in_chars() {
+        let line = SourceLine {
+            number: 1,
+            text: "é#x",
+        };
+        let err = line.error_char(3, "unexpected character");
+
+        assert_eq!((1, 3, "x"), (err.line, err.column, err.text.as_str()));
+    }
+}
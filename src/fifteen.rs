@@ -1,177 +1,156 @@
-extern crate priority_queue;
+use crate::error::{ParseError, SourceLine};
+use crate::grid::{Grid, Position};
+use crate::params::Params;
+use crate::search::dijkstra;
+use crate::solution::Solution;
+use crate::visualize::{gradient, Frames, RED};
 
-use std::collections::{HashMap, HashSet};
-use std::io;
-use std::io::prelude::*;
+pub struct Solver;
 
-use priority_queue::PriorityQueue;
+impl Solution for Solver {
+    const DAY: u32 = 15;
+    const PARAMS: &'static [&'static str] = &["tiles"];
 
-pub fn part_one() {
-    let graph = read_input(1);
-    let answer = get_lowest_risk_path(&graph);
+    type Input = Graph;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-    println!("{}", answer);
-}
+    fn parse(input: &str) -> Result<Graph, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(graph: &Graph) -> i32 {
+        Self::part_one_with(graph, &Params::default()).unwrap()
+    }
+
+    fn part_two(graph: &Graph) -> i32 {
+        Self::part_two_with(graph, &Params::default()).unwrap()
+    }
 
-pub fn part_two() {
-    let graph = read_input(5);
-    let answer = get_lowest_risk_path(&graph);
+    fn part_one_with(graph: &Graph, params: &Params) -> Result<i32, String> {
+        Ok(get_lowest_risk_path(&graph.tiled(get_tiles(params, 1)?)))
+    }
 
-    println!("{}", answer);
+    fn part_two_with(graph: &Graph, params: &Params) -> Result<i32, String> {
+        Ok(get_lowest_risk_path(&graph.tiled(get_tiles(params, 5)?)))
+    }
+
+    fn visualize(
+        graph: &Graph,
+        part: &str,
+        params: &Params,
+        frames: &mut Frames,
+    ) -> Result<(), String> {
+        let graph = match part {
+            "one" => graph.tiled(get_tiles(params, 1)?),
+            _ => graph.tiled(get_tiles(params, 5)?),
+        };
+
+        let mut map = graph
+            .grid
+            .map(|risk| gradient([30, 30, 30], [200, 200, 200], *risk as f64 / 9.0));
+        frames.write(&map, |rgb| *rgb)?;
+
+        let (path, _) = find_lowest_risk_path(&graph);
+        for node in path {
+            map[node] = RED;
+        }
+        frames.write(&map, |rgb| *rgb)?;
+
+        Ok(())
+    }
+}
+
+/// Gets how many times across and down the map is repeated to make the full cave.
+fn get_tiles(params: &Params, default: usize) -> Result<usize, String> {
+    match params.get("tiles", default)? {
+        0 => Err("tiles must be at least 1".to_string()),
+        tiles => Ok(tiles),
+    }
 }
 
-type Node = (i32, i32);
+type Node = Position;
 
 #[derive(Debug)]
-struct Graph {
-    grid: Vec<Vec<i32>>,
-    multiplier: i32,
+pub struct Graph {
+    grid: Grid<i32>,
 }
 
 impl Graph {
-    fn from_lines(lines: &[String], multiplier: i32) -> Graph {
-        let mut rows = vec![];
+    fn from_lines(lines: &[SourceLine]) -> Result<Graph, ParseError> {
+        let grid = Grid::from_lines(lines, "expected a risk level", |c| {
+            c.to_digit(10).map(|risk| risk as i32)
+        })?;
 
-        for line in lines.iter() {
-            let row: Vec<i32> = line
-                .chars()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect();
+        Ok(Graph { grid })
+    }
 
-            rows.push(row);
-        }
+    fn tiled(&self, multiplier: usize) -> Graph {
+        let grid = self.grid.tiled(multiplier, multiplier, |risk, x, y| {
+            (risk + x as i32 + y as i32 - 1) % 9 + 1
+        });
 
-        Graph {
-            grid: rows,
-            multiplier,
-        }
+        Graph { grid }
     }
 
     fn _print(&self) {
-        for y in 0..self.grid.len() * self.multiplier as usize {
-            if y % self.grid.len() == 0 {
-                println!("=======================================");
-            }
-            for x in 0..self.grid[0].len() * self.multiplier as usize {
-                if x % self.grid[0].len() == 0 {
-                    print!("|");
-                }
-                print!("{}", self.get_risk((y as i32, x as i32)));
-            }
-            println!();
-        }
+        println!("{}", self.grid);
     }
 
     fn get_risk(&self, node: Node) -> i32 {
-        let (y, x) = node;
-
-        let dist = (y / self.grid.len() as i32) + (x / self.grid.len() as i32);
-
-        (((self.grid[y as usize % self.grid.len()][x as usize % self.grid[0].len()] + dist) - 1)
-            % 9)
-            + 1
+        self.grid[node]
     }
 
     fn get_neighbors(&self, node: Node) -> Vec<Node> {
-        let mut neighbors = vec![];
-        for y_delta in -1..2 {
-            for x_delta in -1..2 {
-                if y_delta != 0 && x_delta != 0 {
-                    continue;
-                }
-                if y_delta == 0 && x_delta == 0 {
-                    continue;
-                }
-
-                let (y, x) = node;
-                let new_pos = (y + y_delta, x + x_delta);
-
-                if new_pos.0 < 0 || new_pos.0 >= self.grid.len() as i32 * self.multiplier {
-                    continue;
-                }
-                if new_pos.1 < 0 || new_pos.1 >= self.grid[0].len() as i32 * self.multiplier {
-                    continue;
-                }
-
-                neighbors.push(new_pos);
-            }
-        }
+        let (x, y) = node;
 
-        neighbors
+        self.grid.orthogonal_neighbors(x, y).collect()
     }
+}
 
-    fn shortest_path(&self, src: Node, dest: Node) -> Option<Vec<Node>> {
-        let mut distances: PriorityQueue<Node, i32> = PriorityQueue::new();
-        let mut paths: HashMap<Node, Vec<Node>> = HashMap::new();
-        let mut visited: HashSet<Node> = HashSet::new();
-
-        if src == dest {
-            return Some(vec![dest]);
-        }
-
-        paths.insert(src, vec![]);
-        distances.push(src, 0);
-
-        loop {
-            let (cur, _) = distances.pop().unwrap();
-
-            let path = paths.get(&cur).unwrap().clone();
-
-            visited.insert(cur);
-
-            for n in self.get_neighbors(cur) {
-                if visited.contains(&n) {
-                    continue;
-                }
+fn read_input(input: &str) -> Result<Graph, ParseError> {
+    let lines: Vec<SourceLine> = SourceLine::all(input).collect();
 
-                let mut new_path = path.clone();
-                new_path.push(n);
-                let new_weight: i32 = new_path.iter().map(|n| self.get_risk(*n)).sum();
+    Graph::from_lines(&lines)
+}
 
-                if n == dest {
-                    return Some(new_path);
-                }
+fn find_lowest_risk_path(graph: &Graph) -> (Vec<Node>, i32) {
+    let dest = (graph.grid.width() - 1, graph.grid.height() - 1);
+
+    dijkstra(
+        (0, 0),
+        |n| *n == dest,
+        |n| {
+            graph
+                .get_neighbors(*n)
+                .into_iter()
+                .map(|m| (m, graph.get_risk(m)))
+        },
+    )
+    .unwrap()
+}
 
-                if paths.contains_key(&n) {
-                    let (_, &(mut prev_weight)) = distances.get(&n).unwrap();
-                    prev_weight *= -1;
+fn get_lowest_risk_path(graph: &Graph) -> i32 {
+    let (_, risk) = find_lowest_risk_path(graph);
 
-                    if new_weight < prev_weight {
-                        paths.insert(n, new_path);
-                        distances.push(n, -new_weight);
-                    }
-                } else {
-                    paths.insert(n, new_path);
-                    distances.push(n, -new_weight);
-                }
-            }
-        }
-    }
+    risk
 }
 
-fn read_input(multiplier: i32) -> Graph {
-    let stdin = io::stdin();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut lines: Vec<String> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap().to_string();
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/15_example.txt")).unwrap();
 
-        lines.push(line);
+        assert_eq!(40, Solver::part_one(&input));
     }
 
-    Graph::from_lines(&lines, multiplier)
-}
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/15_example.txt")).unwrap();
 
-fn get_lowest_risk_path(graph: &Graph) -> i32 {
-    let path = graph
-        .shortest_path(
-            (0, 0),
-            (
-                graph.grid.len() as i32 * graph.multiplier - 1,
-                graph.grid[0].len() as i32 * graph.multiplier - 1,
-            ),
-        )
-        .unwrap();
-
-    path.iter().map(|n| graph.get_risk(*n)).sum()
+        assert_eq!(315, Solver::part_two(&input));
+    }
 }
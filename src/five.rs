@@ -1,66 +1,59 @@
-use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let lines = read_input();
-    let answer = get_hori_verti_num_at_least_two_overlaps(&lines);
+use crate::error::{ParseError, SourceLine};
+use crate::geometry::Point2;
+use crate::parsing::match_pattern;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
-
-pub fn part_two() {
-    let lines = read_input();
-    let answer = get_hori_verti_diag_num_at_least_two_overlaps(&lines);
-
-    println!("{}", answer);
-}
+pub struct Solver;
 
-fn read_input() -> Vec<Line> {
-    let stdin = io::stdin();
+impl Solution for Solver {
+    const DAY: u32 = 5;
 
-    let mut lines: Vec<Line> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
+    type Input = Vec<Line>;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-        lines.push(Line::from_str(&line).unwrap());
+    fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+        read_input(input)
     }
 
-    lines
-}
+    fn part_one(lines: &Vec<Line>) -> i32 {
+        get_hori_verti_num_at_least_two_overlaps(lines)
+    }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
+    fn part_two(lines: &Vec<Line>) -> i32 {
+        get_hori_verti_diag_num_at_least_two_overlaps(lines)
+    }
 }
 
-impl Point {
-    fn from_str(point_str: &str) -> Option<Point> {
-        let parts: Vec<&str> = point_str.split(',').collect();
-
-        let x: i32 = parts[0].parse().unwrap();
-        let y: i32 = parts[1].parse().unwrap();
-
-        Some(Point { x, y })
+fn read_input(input: &str) -> Result<Vec<Line>, ParseError> {
+    let mut lines: Vec<Line> = Vec::new();
+    for line in SourceLine::all(input) {
+        lines.push(Line::from_str(&line)?);
     }
+
+    Ok(lines)
 }
 
 #[derive(Debug)]
-struct Line {
-    start: Point,
-    end: Point,
+pub struct Line {
+    start: Point2,
+    end: Point2,
 }
 
 impl Line {
-    fn from_str(point_str: &str) -> Option<Line> {
-        let parts: Vec<&str> = point_str.split(" -> ").collect();
+    fn from_str(line: &SourceLine) -> Result<Line, ParseError> {
+        let parts = match_pattern(
+            line,
+            "{} -> {}",
+            "expected a line of the form x1,y1 -> x2,y2",
+        )?;
 
-        let start = Point::from_str(parts[0]).unwrap();
-        let end = Point::from_str(parts[1]).unwrap();
+        let start = Point2::from_str(line, parts[0])?;
+        let end = Point2::from_str(line, parts[1])?;
 
-        Some(Line { start, end })
+        Ok(Line { start, end })
     }
 
     fn is_horizontal(&self) -> bool {
@@ -71,76 +64,23 @@ impl Line {
         self.start.x == self.end.x
     }
 
-    fn mark_points(&self, grid: &mut HashMap<Point, i32>) {
-        if self.is_horizontal() {
-            let dir = sign(self.end.x - self.start.x);
-            let y = self.start.y;
-            let mut x = self.start.x;
-            loop {
-                let point = Point { x, y };
-                if let std::collections::hash_map::Entry::Vacant(e) = grid.entry(point) {
-                    e.insert(1);
-                } else {
-                    *grid.get_mut(&point).unwrap() += 1;
-                }
-
-                if x == self.end.x {
-                    break;
-                }
-                x += dir;
-            }
-        } else if self.is_vertical() {
-            let dir = sign(self.end.y - self.start.y);
-            let x = self.start.x;
-            let mut y = self.start.y;
-            loop {
-                let point = Point { x, y };
-                if let std::collections::hash_map::Entry::Vacant(e) = grid.entry(point) {
-                    e.insert(1);
-                } else {
-                    *grid.get_mut(&point).unwrap() += 1;
-                }
-
-                if y == self.end.y {
-                    break;
-                }
-                y += dir;
-            }
-        } else {
-            let delta_x = sign(self.end.x - self.start.x);
-            let delta_y = sign(self.end.y - self.start.y);
-
-            let mut x = self.start.x;
-            let mut y = self.start.y;
-
-            loop {
-                let point = Point { x, y };
-                if let std::collections::hash_map::Entry::Vacant(e) = grid.entry(point) {
-                    e.insert(1);
-                } else {
-                    *grid.get_mut(&point).unwrap() += 1;
-                }
-
-                if y == self.end.y {
-                    break;
-                }
-                y += delta_y;
-                x += delta_x;
+    fn mark_points(&self, grid: &mut HashMap<Point2, i32>) {
+        let step = (self.end - self.start).signum();
+
+        let mut point = self.start;
+        loop {
+            *grid.entry(point).or_insert(0) += 1;
+
+            if point == self.end {
+                break;
             }
+            point += step;
         }
     }
 }
 
-fn sign(value: i32) -> i32 {
-    match value.cmp(&0) {
-        Ordering::Less => -1,
-        Ordering::Greater => 1,
-        _ => panic!(),
-    }
-}
-
 fn get_hori_verti_num_at_least_two_overlaps(lines: &[Line]) -> i32 {
-    let mut grid: HashMap<Point, i32> = HashMap::new();
+    let mut grid: HashMap<Point2, i32> = HashMap::new();
 
     for line in lines.iter() {
         if line.is_horizontal() || line.is_vertical() {
@@ -152,7 +92,7 @@ fn get_hori_verti_num_at_least_two_overlaps(lines: &[Line]) -> i32 {
 }
 
 fn get_hori_verti_diag_num_at_least_two_overlaps(lines: &[Line]) -> i32 {
-    let mut grid: HashMap<Point, i32> = HashMap::new();
+    let mut grid: HashMap<Point2, i32> = HashMap::new();
 
     for line in lines.iter() {
         line.mark_points(&mut grid);
@@ -160,3 +100,30 @@ fn get_hori_verti_diag_num_at_least_two_overlaps(lines: &[Line]) -> i32 {
 
     grid.iter().filter(|(_k, v)| **v >= 2).count() as i32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/5_example.txt")).unwrap();
+
+        assert_eq!(5, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/5_example.txt")).unwrap();
+
+        assert_eq!(12, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = Solver::parse("0,9 -> 5,9\n1,1 -> 2,b").err().unwrap();
+
+        assert_eq!((2, 10), (err.line, err.column));
+        assert_eq!("b", err.text);
+    }
+}
@@ -1,22 +1,37 @@
-use std::io;
-use std::io::prelude::*;
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::parsing::{parse_comma_list, parse_whitespace_list, sections};
+use crate::solution::Solution;
 
-pub fn part_one() {
-    let (numbers, mut boards) = read_input();
-    let answer = find_first_winning_board(&numbers, &mut boards).unwrap();
+pub struct Solver;
 
-    println!("{}", answer);
-}
+impl Solution for Solver {
+    const DAY: u32 = 4;
+
+    type Input = (Vec<i32>, Vec<Board>);
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<(Vec<i32>, Vec<Board>), ParseError> {
+        read_input(input)
+    }
 
-pub fn part_two() {
-    let (numbers, mut boards) = read_input();
-    let answer = find_last_winning_board(&numbers, &mut boards).unwrap();
+    fn part_one(input: &(Vec<i32>, Vec<Board>)) -> i32 {
+        let (numbers, boards) = input;
+        let mut boards = boards.clone();
 
-    println!("{}", answer);
+        find_first_winning_board(numbers, &mut boards).unwrap()
+    }
+
+    fn part_two(input: &(Vec<i32>, Vec<Board>)) -> i32 {
+        let (numbers, boards) = input;
+        let mut boards = boards.clone();
+
+        find_last_winning_board(numbers, &mut boards).unwrap()
+    }
 }
 
-#[derive(Debug)]
-struct Board {
+#[derive(Clone, Debug)]
+pub struct Board {
     tiles: [[i32; 5]; 5],
     markings: [[bool; 5]; 5],
 }
@@ -99,60 +114,61 @@ impl Board {
         sum
     }
 
-    fn from_lines(lines: &[String]) -> Option<Board> {
+    fn from_lines(lines: &[SourceLine]) -> Result<Board, ParseError> {
         let mut tiles: [[i32; 5]; 5] = [[0; 5]; 5];
         for (r, line) in lines.iter().enumerate() {
-            let numbers: Vec<i32> = line
-                .split_whitespace()
-                .map(|n| n.parse().unwrap())
-                .collect();
+            if r >= tiles.len() {
+                return Err(line.error_line("expected a board to have 5 rows"));
+            }
 
-            for (c, num) in numbers.iter().enumerate() {
-                tiles[r][c] = *num;
+            let numbers: Vec<i32> = parse_whitespace_list(line, line.text, "expected a number")?;
+            if numbers.len() != tiles[r].len() {
+                return Err(line.error_line("expected a row of 5 numbers"));
             }
+
+            tiles[r].copy_from_slice(&numbers);
+        }
+
+        if lines.len() < tiles.len() {
+            let last = lines.last().unwrap();
+            return Err(missing_line(
+                last.number + 1,
+                "expected a board to have 5 rows",
+            ));
         }
 
         let markings = [[false; 5]; 5];
 
-        Some(Board { tiles, markings })
+        Ok(Board { tiles, markings })
     }
 }
 
-fn read_input() -> (Vec<i32>, Vec<Board>) {
-    let stdin = io::stdin();
+fn read_input(input: &str) -> Result<(Vec<i32>, Vec<Board>), ParseError> {
+    let sections = sections(input);
 
-    let mut boards: Vec<Board> = Vec::new();
-    let mut lines: Vec<String> = stdin.lock().lines().map(|l| l.unwrap()).collect();
-
-    let numbers: Vec<i32> = lines
-        .first()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse().unwrap())
-        .collect();
-    lines.remove(0);
-
-    let mut current_board_lines: Vec<String> = vec![];
-    for line in lines.iter() {
-        if line.is_empty() {
-            if !current_board_lines.is_empty() {
-                boards.push(Board::from_lines(&current_board_lines).unwrap());
-
-                current_board_lines.clear();
-            }
-            continue;
-        }
+    let first_line = match sections.first() {
+        Some(lines) => lines[0],
+        None => return Err(missing_line(1, "expected a list of drawn numbers")),
+    };
+    let numbers: Vec<i32> =
+        parse_comma_list(&first_line, first_line.text, "expected a drawn number")?;
 
-        current_board_lines.push(line.to_string());
+    let mut boards: Vec<Board> = Vec::new();
+    if sections[0].len() > 1 {
+        boards.push(Board::from_lines(&sections[0][1..])?);
+    }
+    for lines in sections[1..].iter() {
+        boards.push(Board::from_lines(lines)?);
     }
 
-    if !current_board_lines.is_empty() {
-        boards.push(Board::from_lines(&current_board_lines).unwrap());
-
-        current_board_lines.clear();
+    if boards.is_empty() {
+        return Err(missing_line(
+            input.lines().count() + 1,
+            "expected at least one board",
+        ));
     }
 
-    (numbers, boards)
+    Ok((numbers, boards))
 }
 
 fn find_first_winning_board(numbers: &[i32], boards: &mut [Board]) -> Option<i32> {
@@ -185,3 +201,29 @@ fn find_last_winning_board(numbers: &[i32], boards: &mut [Board]) -> Option<i32>
 
     Some(last_win_number)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/4_example.txt")).unwrap();
+
+        assert_eq!(4512, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/4_example.txt")).unwrap();
+
+        assert_eq!(1924, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn no_boards() {
+        let err = Solver::parse("1,2,3\n").unwrap_err();
+
+        assert_eq!(2, err.line);
+    }
+}
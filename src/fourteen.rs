@@ -1,22 +1,48 @@
 use std::collections::BTreeMap;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let (template, rules) = read_input();
-    let answer = solve_1(&template, &rules);
+use num_bigint::BigInt;
 
-    println!("{}", answer);
-}
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::params::Params;
+use crate::parsing::match_pattern;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 14;
+    const PARAMS: &'static [&'static str] = &["steps"];
+
+    type Input = (String, Vec<Rule>);
+    type PartOne = i32;
+    type PartTwo = BigInt;
+
+    fn parse(input: &str) -> Result<(String, Vec<Rule>), ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(input: &(String, Vec<Rule>)) -> i32 {
+        Self::part_one_with(input, &Params::default()).unwrap()
+    }
+
+    fn part_two(input: &(String, Vec<Rule>)) -> BigInt {
+        Self::part_two_with(input, &Params::default()).unwrap()
+    }
 
-pub fn part_two() {
-    let (template, rules) = read_input();
-    let answer = solve_2(&template, &rules);
+    fn part_one_with(input: &(String, Vec<Rule>), params: &Params) -> Result<i32, String> {
+        let (template, rules) = input;
 
-    println!("{}", answer);
+        Ok(solve_1(template, rules, params.get("steps", 10)?))
+    }
+
+    fn part_two_with(input: &(String, Vec<Rule>), params: &Params) -> Result<BigInt, String> {
+        let (template, rules) = input;
+
+        Ok(solve_2(template, rules, params.get("steps", 40)?))
+    }
 }
 
-struct Rule {
+pub struct Rule {
     start: char,
     end: char,
     fill: char,
@@ -38,38 +64,49 @@ impl Rule {
     }
 }
 
-fn read_input() -> (String, Vec<Rule>) {
-    let stdin = io::stdin();
-
+fn read_input(input: &str) -> Result<(String, Vec<Rule>), ParseError> {
     let mut template: Option<String> = None;
     let mut rules: Vec<Rule> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-
+    for line in SourceLine::all(input) {
         if template.is_none() {
-            template = Some(line.to_string());
+            if line.text.chars().count() < 2 {
+                return Err(line.error_line("expected a polymer template"));
+            }
+
+            template = Some(line.text.to_string());
             continue;
-        } else if line.is_empty() {
+        } else if line.text.is_empty() {
             continue;
         }
 
-        let parts: Vec<&str> = line.split(" -> ").collect();
+        let parts = match_pattern(&line, "{} -> {}", "expected a rule of the form AB -> C")?;
+
         let parts_ab: Vec<char> = parts[0].chars().collect();
+        let parts_c: Vec<char> = parts[1].chars().collect();
+        if parts_ab.len() != 2 {
+            return Err(line.error(parts[0], "expected a pair of elements"));
+        }
+        if parts_c.len() != 1 {
+            return Err(line.error(parts[1], "expected a single element"));
+        }
 
         let a = parts_ab[0];
         let b = parts_ab[1];
-        let c = parts[1].chars().last().unwrap();
+        let c = parts_c[0];
 
         rules.push(Rule::from_tuple(&(a, b, c)));
     }
 
-    (template.unwrap(), rules)
+    match template {
+        Some(template) => Ok((template, rules)),
+        None => Err(missing_line(1, "expected a polymer template")),
+    }
 }
 
-fn solve_1(template: &str, rules: &[Rule]) -> i32 {
+fn solve_1(template: &str, rules: &[Rule], steps: u32) -> i32 {
     let mut current_string = template.to_string();
 
-    for _ in 0..10 {
+    for _ in 0..steps {
         let mut new_chars: Vec<char> = vec![current_string.chars().collect::<Vec<char>>()[0]];
 
         for (cur, next) in current_string.chars().zip(current_string.chars().skip(1)) {
@@ -100,24 +137,20 @@ fn solve_1(template: &str, rules: &[Rule]) -> i32 {
     most_common_count - least_common_count
 }
 
-fn to_pairs(string: &str) -> BTreeMap<(char, char), i64> {
-    let mut counts: BTreeMap<(char, char), i64> = BTreeMap::new();
+fn to_pairs(string: &str) -> BTreeMap<(char, char), BigInt> {
+    let mut counts: BTreeMap<(char, char), BigInt> = BTreeMap::new();
     for ab in string.chars().zip(string.chars().skip(1)) {
-        if counts.contains_key(&ab) {
-            counts.insert(ab, counts[&ab] + 1);
-        } else {
-            counts.insert(ab, 1);
-        }
+        *counts.entry(ab).or_default() += 1;
     }
 
     counts
 }
 
-fn solve_2(template: &str, rules: &[Rule]) -> i64 {
+fn solve_2(template: &str, rules: &[Rule], steps: u32) -> BigInt {
     let mut current_pairs = to_pairs(template);
 
-    for _ in 0..40 {
-        let mut new_pairs = BTreeMap::new();
+    for _ in 0..steps {
+        let mut new_pairs: BTreeMap<(char, char), BigInt> = BTreeMap::new();
 
         for ((cur, next), count) in current_pairs.iter() {
             let mut any_applied = false;
@@ -125,42 +158,22 @@ fn solve_2(template: &str, rules: &[Rule]) -> i64 {
                 if rule.applies(*cur, *next) {
                     any_applied = true;
 
-                    let np = (*cur, rule.fill);
-                    if new_pairs.contains_key(&np) {
-                        new_pairs.insert(np, new_pairs[&np] + 1 * count);
-                    } else {
-                        new_pairs.insert(np, 1 * count);
-                    }
-
-                    let np = (rule.fill, *next);
-                    if new_pairs.contains_key(&np) {
-                        new_pairs.insert(np, new_pairs[&np] + 1 * count);
-                    } else {
-                        new_pairs.insert(np, 1 * count);
-                    }
+                    *new_pairs.entry((*cur, rule.fill)).or_default() += count;
+                    *new_pairs.entry((rule.fill, *next)).or_default() += count;
                 }
             }
 
             if !any_applied {
-                let ab = (*cur, *next);
-                if new_pairs.contains_key(&ab) {
-                    new_pairs.insert(ab, new_pairs[&ab] + 1 * count);
-                } else {
-                    new_pairs.insert(ab, 1 * count);
-                }
+                *new_pairs.entry((*cur, *next)).or_default() += count;
             }
         }
 
         current_pairs = new_pairs;
     }
 
-    let mut frequency_table: BTreeMap<char, i64> = BTreeMap::new();
+    let mut frequency_table: BTreeMap<char, BigInt> = BTreeMap::new();
     for ((c, _), count) in current_pairs.iter() {
-        if frequency_table.contains_key(&c) {
-            frequency_table.insert(*c, frequency_table[&c] + 1 * count);
-        } else {
-            frequency_table.insert(*c, 1 * count);
-        }
+        *frequency_table.entry(*c).or_default() += count;
     }
 
     let most_common_count = frequency_table.values().max().unwrap();
@@ -168,3 +181,31 @@ fn solve_2(template: &str, rules: &[Rule]) -> i64 {
 
     most_common_count - least_common_count + 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::read_params;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/14_example.txt")).unwrap();
+
+        assert_eq!(1588, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/14_example.txt")).unwrap();
+
+        assert_eq!(BigInt::from(2188189693529i64), Solver::part_two(&input));
+    }
+
+    #[test]
+    fn steps_beyond_i64() {
+        let input = Solver::parse(include_str!("../inputs/14_example.txt")).unwrap();
+        let params = read_params(vec!["steps=100"]).unwrap();
+
+        assert!(Solver::part_two_with(&input, &params).unwrap() > BigInt::from(i64::MAX));
+    }
+}
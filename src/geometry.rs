@@ -0,0 +1,294 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::error::{ParseError, SourceLine};
+
+/// A point or vector in two dimensions.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub fn new(x: i32, y: i32) -> Point2 {
+        Point2 { x, y }
+    }
+
+    /// Parses a point of the form `x,y` from `part`, which should be a slice of `line`.
+    pub fn from_str(line: &SourceLine, part: &str) -> Result<Point2, ParseError> {
+        let coords: Vec<&str> = part.split(',').collect();
+        if coords.len() != 2 {
+            return Err(line.error(part, "expected a point of the form x,y"));
+        }
+
+        let x = line.parse(coords[0], "expected an x coordinate")?;
+        let y = line.parse(coords[1], "expected a y coordinate")?;
+
+        Ok(Point2 { x, y })
+    }
+
+    pub fn manhattan(&self, other: &Point2) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point2) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Returns a vector with each coordinate replaced by its sign, for stepping one unit at a time.
+    pub fn signum(&self) -> Point2 {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point2 {
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i32> for Point2 {
+    type Output = Point2;
+
+    fn mul(self, scale: i32) -> Point2 {
+        Point2::new(self.x * scale, self.y * scale)
+    }
+}
+
+/// A point or vector in three dimensions.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: i32, y: i32, z: i32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// Parses a point of the form `x,y,z` from `part`, which should be a slice of `line`.
+    pub fn from_str(line: &SourceLine, part: &str) -> Result<Point3, ParseError> {
+        let coords: Vec<&str> = part.split(',').collect();
+        if coords.len() != 3 {
+            return Err(line.error(part, "expected a point of the form x,y,z"));
+        }
+
+        let x = line.parse(coords[0], "expected an x coordinate")?;
+        let y = line.parse(coords[1], "expected a y coordinate")?;
+        let z = line.parse(coords[2], "expected a z coordinate")?;
+
+        Ok(Point3 { x, y, z })
+    }
+
+    pub fn manhattan(&self, other: &Point3) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point3) -> i32 {
+        (self.x - other.x)
+            .abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+
+    pub fn rotated(&self, rotation: &Rotation3) -> Point3 {
+        let coords = [self.x, self.y, self.z];
+        let row = |i: usize| -> i32 { (0..3).map(|j| rotation.matrix[i][j] * coords[j]).sum() };
+
+        Point3::new(row(0), row(1), row(2))
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Point3) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, other: Point3) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i32> for Point3 {
+    type Output = Point3;
+
+    fn mul(self, scale: i32) -> Point3 {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+/// A rotation in three dimensions by some multiple of 90 degrees about each axis.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rotation3 {
+    matrix: [[i32; 3]; 3],
+}
+
+impl Rotation3 {
+    pub const IDENTITY: Rotation3 = Rotation3 {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// Returns all 24 rotations, starting with the identity.
+    pub fn all() -> Vec<Rotation3> {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        let mut rotations = vec![];
+        for axes in permutations.iter() {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for (i, axis) in axes.iter().enumerate() {
+                    matrix[i][*axis] = if signs & (1 << i) == 0 { 1 } else { -1 };
+                }
+
+                let rotation = Rotation3 { matrix };
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+
+        rotations
+    }
+
+    /// Returns the rotation that applies this rotation followed by `other`.
+    pub fn then(&self, other: &Rotation3) -> Rotation3 {
+        let mut matrix = [[0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| other.matrix[i][k] * self.matrix[k][j]).sum();
+            }
+        }
+
+        Rotation3 { matrix }
+    }
+
+    fn determinant(&self) -> i32 {
+        let m = &self.matrix;
+
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point2_arithmetic() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(4, 2);
+
+        assert_eq!(Point2::new(5, 0), a + b);
+        assert_eq!(Point2::new(3, 4), b - a);
+        assert_eq!(Point2::new(-1, 2), -a);
+        assert_eq!(7, a.manhattan(&b));
+        assert_eq!(4, a.chebyshev(&b));
+        assert_eq!(Point2::new(1, 1), (b - a).signum());
+    }
+
+    #[test]
+    fn point_parsing() {
+        let line = SourceLine {
+            number: 1,
+            text: "3,-4 5,6,x",
+        };
+
+        assert_eq!(
+            Point2::new(3, -4),
+            Point2::from_str(&line, &line.text[..4]).unwrap()
+        );
+
+        let err = Point3::from_str(&line, &line.text[5..]).unwrap_err();
+        assert_eq!(10, err.column);
+    }
+
+    #[test]
+    fn rotations() {
+        let rotations = Rotation3::all();
+        let p = Point3::new(1, 2, 3);
+
+        assert_eq!(24, rotations.len());
+        assert_eq!(Rotation3::IDENTITY, rotations[0]);
+
+        let rotated: std::collections::HashSet<Point3> =
+            rotations.iter().map(|r| p.rotated(r)).collect();
+        assert_eq!(24, rotated.len());
+
+        for a in rotations.iter() {
+            for b in rotations.iter() {
+                assert_eq!(p.rotated(a).rotated(b), p.rotated(&a.then(b)));
+            }
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{missing_line, ParseError, SourceLine};
+
+/// A position in a grid, as `(x, y)` with `(0, 0)` in the top left corner.
+pub type Position = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const DIAGONAL: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+const ALL: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid of values, stored row by row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its rows, returning `None` if they are not all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a grid with one character per cell from lines of input, reporting `message` for
+    /// any character that `parse_cell` rejects.
+    pub fn from_lines<F>(
+        lines: &[SourceLine],
+        message: &str,
+        parse_cell: F,
+    ) -> Result<Grid<T>, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let mut rows: Vec<Vec<T>> = vec![];
+        for line in lines.iter() {
+            let mut row = vec![];
+            for (i, c) in line.text.char_indices() {
+                match parse_cell(c) {
+                    Some(value) => row.push(value),
+                    None => return Err(line.error_char(i, message)),
+                }
+            }
+
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(
+                        line.error_line(&format!("expected a row of {} characters", first.len()))
+                    );
+                }
+            }
+
+            rows.push(row);
+        }
+
+        if rows.is_empty() || rows[0].is_empty() {
+            return Err(missing_line(
+                lines.first().map(|line| line.number).unwrap_or(1),
+                "expected a grid",
+            ));
+        }
+
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    /// Parses a grid from every line of the given input. See `from_lines`.
+    pub fn parse<F>(input: &str, message: &str, parse_cell: F) -> Result<Grid<T>, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let lines: Vec<SourceLine> = SourceLine::all(input).collect();
+
+        Grid::from_lines(&lines, message, parse_cell)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a value at a position that may be off the edges of the grid.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<&T> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        self.get(x as usize, y as usize)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the value at a position, returning the old value, or `None` if the position is
+    /// not in the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Returns the position offset from `(x, y)` by `(dx, dy)`, if it is in the grid.
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<Position> {
+        let x = x as isize + dx;
+        let y = y as isize + dy;
+
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the positions above, left of, right of, and below `(x, y)` that are in the grid.
+    pub fn orthogonal_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_by(x, y, &ORTHOGONAL)
+    }
+
+    /// Returns the positions diagonally adjacent to `(x, y)` that are in the grid.
+    pub fn diagonal_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_by(x, y, &DIAGONAL)
+    }
+
+    /// Returns all eight positions around `(x, y)` that are in the grid.
+    pub fn all_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_by(x, y, &ALL)
+    }
+
+    fn neighbors_by(
+        &self,
+        x: usize,
+        y: usize,
+        deltas: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + '_ {
+        deltas
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(x, y, dx, dy))
+    }
+
+    /// Iterates over every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterates over every position in the grid along with its value, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Creates a new grid of the same size by applying `f` to every value.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Creates a grid `across` copies wide and `down` copies tall, where each value in the copy
+    /// at `(tile_x, tile_y)` is made by `f(value, tile_x, tile_y)`.
+    pub fn tiled<F>(&self, across: usize, down: usize, f: F) -> Grid<T>
+    where
+        F: Fn(&T, usize, usize) -> T,
+    {
+        let width = self.width * across;
+        let height = self.height * down;
+
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let value = &self[(x % self.width, y % self.height)];
+                cells.push(f(value, x / self.width, y / self.height));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Draws the grid with one character per cell, without a trailing newline.
+    pub fn render<F>(&self, to_char: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        self.rows()
+            .map(|row| row.iter().map(&to_char).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Position) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("position ({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Position) -> &mut T {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("position ({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for value in row.iter() {
+                write!(f, "{}", value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", "expected a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Grid::parse("12\n1x", "expected a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+
+        let err = Grid::parse("12\n123", "expected a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+
+        assert!(Grid::parse("", "expected a digit", |c| c.to_digit(10)).is_err());
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut grid = digits();
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, grid.get_signed(-1, 0));
+        assert_eq!(Some(4), grid.set(0, 1, 9));
+        assert_eq!(None, grid.set(0, 2, 9));
+        assert_eq!(9, grid[(0, 1)]);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = digits();
+
+        assert_eq!(
+            vec![(0, 0), (2, 0), (1, 1)],
+            grid.orthogonal_neighbors(1, 0).collect::<Vec<Position>>()
+        );
+        assert_eq!(
+            vec![(1, 0)],
+            grid.diagonal_neighbors(0, 1).collect::<Vec<Position>>()
+        );
+        assert_eq!(5, grid.all_neighbors(1, 1).count());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits();
+
+        assert_eq!(
+            vec![&[1, 2, 3][..], &[4, 5, 6][..]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![2, 5], grid.column(1).copied().collect::<Vec<u32>>());
+        assert_eq!(3, grid.columns().count());
+    }
+
+    #[test]
+    fn tiled_and_display() {
+        let grid = digits().tiled(2, 2, |v, x, y| (v + (x + y) as u32) % 10);
+
+        assert_eq!("123234\n456567\n234345\n567678", grid.to_string());
+        assert_eq!(
+            "#.#\n.#.",
+            digits().render(|v| if v % 2 == 1 { '#' } else { '.' })
+        );
+    }
+}
@@ -1,17 +1,29 @@
+pub mod answer;
+pub mod answers;
 pub mod eight;
 pub mod eighteen;
 pub mod eleven;
+pub mod error;
 pub mod fifteen;
 pub mod five;
 pub mod four;
 pub mod fourteen;
+pub mod geometry;
+pub mod grid;
 pub mod nine;
 pub mod nineteen;
 pub mod one;
+pub mod params;
+pub mod parsing;
+pub mod scaffold;
+pub mod search;
 pub mod seven;
 pub mod seventeen;
+pub mod site;
 pub mod six;
 pub mod sixteen;
+pub mod solution;
+pub mod submissions;
 pub mod ten;
 pub mod thirteen;
 pub mod three;
@@ -19,3 +31,4 @@ pub mod twelve;
 pub mod twenty;
 pub mod twenty_one;
 pub mod two;
+pub mod visualize;
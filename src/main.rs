@@ -1,147 +1,948 @@
 extern crate clap;
-use clap::{App, Arg, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+#[macro_use]
+extern crate serde_json;
+
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 extern crate advent_of_code_2021;
+use advent_of_code_2021::answers::read_answers;
+use advent_of_code_2021::error::ParseError;
+use advent_of_code_2021::one::{depths_from_reader, WindowedSeries};
+use advent_of_code_2021::params::{read_params, Params};
+use advent_of_code_2021::parsing::unescape;
+use advent_of_code_2021::scaffold::create_day;
+use advent_of_code_2021::site::{fetch_input, submit_answer, FetchOutcome, SiteConfig};
+use advent_of_code_2021::solution::{
+    run_part_one, run_part_two, run_visualize, PartRunner, RunError, Solution, Visualizer,
+};
+use advent_of_code_2021::submissions::{read_history, History, Submission, Verdict};
+use advent_of_code_2021::visualize::Frames;
+use advent_of_code_2021::*;
 
 fn main() {
     let a = App::new("advent_of_code_2021").author("Christopher Wells <cwellsny@gmail.com>");
 
+    let a = a
+        .subcommand(add_format_arg(
+            SubCommand::with_name("all")
+                .about("Runs both parts of every day against inputs/<day>.txt"),
+        ))
+        .subcommand(
+            add_input_args(
+                SubCommand::with_name("bench")
+                    .about("Runs a day's part repeatedly and reports min/median/max times")
+                    .arg(
+                        Arg::with_name("day")
+                            .help("Selects the day to run (1, 2, ...)")
+                            .required(true)
+                            .index(1),
+                    )
+                    .arg(
+                        Arg::with_name("part")
+                            .help("Selects the part to run (one, two)")
+                            .required(true)
+                            .index(2),
+                    ),
+            )
+            .arg(
+                Arg::with_name("runs")
+                    .help("Number of times to run the part")
+                    .long("runs")
+                    .short("n")
+                    .takes_value(true)
+                    .default_value("10"),
+            ),
+        )
+        .subcommand(add_site_args(
+            SubCommand::with_name("fetch")
+                .about("Downloads a day's input to inputs/<day>.txt if it is not already there")
+                .arg(
+                    Arg::with_name("day")
+                        .help("Selects the day to download (1, 2, ...)")
+                        .required(true)
+                        .index(1),
+                ),
+        ))
+        .subcommand(
+            SubCommand::with_name("new")
+                .about("Creates the module and empty input files for a new day")
+                .arg(
+                    Arg::with_name("day")
+                        .help("Selects the day to create (1, 2, ...)")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("course")
+                .about("Reports the deepest point of day 2's course, or prints the whole course as CSV")
+                .arg(
+                    Arg::with_name("part")
+                        .help("Selects the part whose movement model to use (one, two)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .help("File of commands to read, or - for stdin")
+                        .default_value("inputs/2.txt")
+                        .index(2),
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .help("Prints the position after every command as CSV")
+                        .long("csv"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("sonar")
+                .about("Streams day 1 depths from a file and reports how its windows change")
+                .arg(
+                    Arg::with_name("input")
+                        .help("File of depths to read, or - for stdin")
+                        .default_value("inputs/1.txt")
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("window")
+                        .help("Number of depths in each window")
+                        .long("window")
+                        .short("w")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name("aggregate")
+                        .help("How the depths in a window are combined (sum, mean, min, max)")
+                        .long("aggregate")
+                        .short("a")
+                        .takes_value(true)
+                        .default_value("sum"),
+                ),
+        )
+        .subcommand(
+            add_site_args(
+                SubCommand::with_name("submit")
+                    .about("Submits the answer for a day's part using inputs/<day>.txt")
+                    .arg(
+                        Arg::with_name("day")
+                            .help("Selects the day to submit (1, 2, ...)")
+                            .required(true)
+                            .index(1),
+                    )
+                    .arg(
+                        Arg::with_name("part")
+                            .help("Selects the part to submit (one, two)")
+                            .required(true)
+                            .index(2),
+                    ),
+            )
+            .arg(
+                Arg::with_name("history")
+                    .help("File that submitted answers and their responses are recorded in")
+                    .long("history")
+                    .takes_value(true)
+                    .default_value("submissions.txt"),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Checks that each day still produces its known answers")
+                .arg(
+                    Arg::with_name("answers")
+                        .help("File of known answers to check against")
+                        .long("answers")
+                        .short("a")
+                        .takes_value(true)
+                        .default_value("answers.txt"),
+                )
+                .arg(
+                    Arg::with_name("day")
+                        .help("Only checks the answers for the given day")
+                        .long("day")
+                        .short("d")
+                        .takes_value(true),
+                ),
+        );
+
     let days = get_days();
     let app = days.iter().map(|d| d.0.clone()).fold(a, |b, day| {
         b.subcommand(
-            SubCommand::with_name(&format!("day{}", day)).arg(
-                Arg::with_name("part")
-                    .help("Selects the part to run (one, two)")
-                    .required(true)
-                    .index(1),
+            add_format_arg(add_input_args(
+                SubCommand::with_name(&format!("day{}", day)).arg(
+                    Arg::with_name("part")
+                        .help("Selects the part to run (one, two)")
+                        .required(true)
+                        .index(1),
+                ),
+            ))
+            .arg(
+                Arg::with_name("time")
+                    .help("Reports how long parsing and solving took")
+                    .long("time")
+                    .short("t"),
+            )
+            .arg(
+                Arg::with_name("visualize")
+                    .help("Writes a PPM image of each step of the solution to the given directory")
+                    .long("visualize")
+                    .takes_value(true)
+                    .value_name("dir"),
             ),
         )
     });
     let matches = app.get_matches();
 
-    for (day, part_one, part_two) in days {
+    if let Some(matches) = matches.subcommand_matches("all") {
+        if !run_all(&days, matches) {
+            process::exit(1);
+        }
+    }
+
+    if let Some(matches) = matches.subcommand_matches("bench") {
+        run_bench(&days, matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("course") {
+        run_course(matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("fetch") {
+        run_fetch(matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("new") {
+        run_new(matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("sonar") {
+        run_sonar(matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("submit") {
+        run_submit(&days, matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        if !run_verify(&days, matches) {
+            process::exit(1);
+        }
+    }
+
+    for (day, part_one, part_two, visualize) in days.iter() {
         let day_string = format!("day{}", day);
 
         if let Some(matches) = matches.subcommand_matches(day_string) {
             let part = matches.value_of("part").unwrap();
 
-            match part {
-                "one" => part_one(),
-                "two" => part_two(),
-                p => println!("Unknown part: {}", p),
+            let run = match part {
+                "one" => part_one,
+                "two" => part_two,
+                p => {
+                    println!("Unknown part: {}", p);
+                    return;
+                }
+            };
+
+            let input = read_input(day, matches);
+            let params = read_params_arg(matches);
+            let result = run(&input, &params).unwrap_or_else(|err| exit_run_error(&err));
+            print_warnings(&result.warnings);
+            match matches.value_of("format") {
+                Some("json") => println!(
+                    "{}",
+                    answer_json(day, part, &result.answer.to_string(), result.total_time())
+                ),
+                _ => println!("{}", result.answer),
+            }
+
+            if matches.is_present("time") {
+                eprintln!("Parse: {:.2?}", result.parse_time);
+                eprintln!("Solve: {:.2?}", result.solve_time);
+            }
+
+            if let Some(dir) = matches.value_of("visualize") {
+                run_visualize_part(*visualize, &input, part, &params, Path::new(dir));
             }
         }
     }
 }
 
-type AdventOfCodeDay = (String, fn(), fn());
+fn add_input_args<'a, 'b>(subcommand: App<'a, 'b>) -> App<'a, 'b> {
+    subcommand
+        .arg(
+            Arg::with_name("input")
+                .help("Input file to read, or - for stdin [default: inputs/<day>.txt]")
+                .long("input")
+                .short("i")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("example")
+                .help("Reads inputs/<day>_example.txt, or inputs/<day>_example_<N>.txt")
+                .long("example")
+                .short("e")
+                .takes_value(true)
+                .min_values(0)
+                .conflicts_with("input"),
+        )
+        .arg(
+            Arg::with_name("text")
+                .help("Uses the given text as the input, with \\n separating lines")
+                .long("text")
+                .takes_value(true)
+                .conflicts_with_all(&["input", "example"]),
+        )
+        .arg(
+            Arg::with_name("param")
+                .help("Sets a named puzzle parameter, such as days=512 for day 6")
+                .long("param")
+                .short("p")
+                .takes_value(true)
+                .value_name("name=value")
+                .multiple(true)
+                .number_of_values(1),
+        )
+}
+
+fn add_format_arg<'a, 'b>(subcommand: App<'a, 'b>) -> App<'a, 'b> {
+    subcommand.arg(
+        Arg::with_name("format")
+            .help("Selects how answers are printed")
+            .long("format")
+            .short("f")
+            .takes_value(true)
+            .possible_values(&["text", "json"])
+            .default_value("text"),
+    )
+}
+
+fn add_site_args<'a, 'b>(subcommand: App<'a, 'b>) -> App<'a, 'b> {
+    subcommand
+        .arg(
+            Arg::with_name("session-file")
+                .help("File containing the session token, used when AOC_SESSION is not set")
+                .long("session-file")
+                .takes_value(true)
+                .default_value(site::DEFAULT_SESSION_FILE),
+        )
+        .arg(
+            Arg::with_name("base-url")
+                .help("Base URL of the puzzle site [default: AOC_BASE_URL or the real site]")
+                .long("base-url")
+                .takes_value(true),
+        )
+}
+
+fn load_site_config(matches: &ArgMatches) -> SiteConfig {
+    let session_file = Path::new(matches.value_of("session-file").unwrap());
+    let config = SiteConfig::load(session_file).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    match matches.value_of("base-url") {
+        Some(base_url) => config.with_base_url(base_url),
+        None => config,
+    }
+}
+
+fn run_fetch(matches: &ArgMatches) {
+    let day = parse_day(matches.value_of("day").unwrap());
+    let config = load_site_config(matches);
+
+    match fetch_input(&config, day, Path::new("inputs")) {
+        Ok(FetchOutcome::Downloaded(path)) => println!("Downloaded {}", path.display()),
+        Ok(FetchOutcome::Cached(path)) => println!("Already downloaded {}", path.display()),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_new(matches: &ArgMatches) {
+    let day = parse_day(matches.value_of("day").unwrap());
+
+    match create_day(Path::new(""), day) {
+        Ok(paths) => {
+            for path in paths.iter() {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_course(matches: &ArgMatches) {
+    let input = match matches.value_of("input").unwrap() {
+        "-" => read_stdin(),
+        path => read_file(Path::new(path)),
+    };
+    let commands = two::Solver::parse(&input)
+        .unwrap_or_else(|err| exit_run_error(&RunError::Parse(err.with_day(2))));
+
+    let course =
+        two::get_course(&commands, matches.value_of("part").unwrap()).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
+
+    if matches.is_present("csv") {
+        print!("{}", two::to_csv(&course));
+    } else {
+        let stats = two::CourseStats::from_trajectory(&course);
+        println!("Max depth: {}", stats.max_depth);
+        println!("Reached at step: {}", stats.max_depth_step);
+    }
+}
+
+fn run_sonar(matches: &ArgMatches) {
+    let series = matches
+        .value_of("window")
+        .unwrap()
+        .parse()
+        .map_err(|_| "window must be a number".to_string())
+        .and_then(|window| {
+            let aggregate = matches.value_of("aggregate").unwrap().parse()?;
+
+            WindowedSeries::new(window, aggregate)
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+
+    let stats = match matches.value_of("input").unwrap() {
+        "-" => series.try_analyze(depths_from_reader(io::stdin().lock())),
+        path => {
+            let file = fs::File::open(path).unwrap_or_else(|err| {
+                eprintln!("Failed to read input file {}: {}", path, err);
+                process::exit(1);
+            });
+
+            series.try_analyze(depths_from_reader(io::BufReader::new(file)))
+        }
+    };
+    let stats = stats.unwrap_or_else(|err| exit_run_error(&RunError::Parse(err.with_day(1))));
+
+    println!("Increases: {}", stats.increases);
+    println!("Decreases: {}", stats.decreases);
+    println!("Plateaus: {}", stats.plateaus);
+    println!("Longest monotonic run: {}", stats.longest_monotonic_run);
+}
+
+fn run_submit(days: &[AdventOfCodeDay], matches: &ArgMatches) {
+    let day = matches.value_of("day").unwrap();
+    let part = matches.value_of("part").unwrap();
+
+    let run = get_part(days, day, part).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let day = parse_day(day);
+
+    let input = read_file(&get_default_input_path(&day.to_string(), None));
+    let answer = run(&input, &Params::default())
+        .unwrap_or_else(|err| exit_run_error(&err))
+        .answer;
+    if answer.is_picture() {
+        eprintln!("Answer is a picture, so it must be read and submitted by hand:");
+        eprintln!("{}", answer);
+        process::exit(1);
+    }
+    let answer = answer.to_string();
+
+    let history_path = Path::new(matches.value_of("history").unwrap());
+    let history = if history_path.exists() {
+        read_history(&read_file(history_path)).unwrap_or_else(|err| {
+            eprintln!(
+                "Failed to read history file {}: {}",
+                history_path.display(),
+                err
+            );
+            process::exit(1);
+        })
+    } else {
+        History::default()
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    if let Err(err) = history.check(day, part, &answer, now) {
+        eprintln!("Not submitting {}: {}", answer, err);
+        process::exit(1);
+    }
+
+    let config = load_site_config(matches);
+    let (verdict, wait_secs) = submit_answer(&config, day, part, &answer).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    let submission = Submission {
+        time: now,
+        day,
+        part: part.to_string(),
+        verdict,
+        wait_secs,
+        answer,
+    };
+    let recorded = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path)
+        .and_then(|mut file| writeln!(file, "{}", submission));
+    if let Err(err) = recorded {
+        eprintln!(
+            "Failed to record submission in {}: {}",
+            history_path.display(),
+            err
+        );
+    }
+
+    println!("{}: {}", submission.answer, verdict);
+    if wait_secs > 0 {
+        println!("Wait {}s before submitting again", wait_secs);
+    }
+
+    if verdict != Verdict::Correct {
+        process::exit(1);
+    }
+}
+
+fn run_visualize_part(visualize: Visualizer, input: &str, part: &str, params: &Params, dir: &Path) {
+    let written = Frames::create(dir).and_then(|mut frames| {
+        visualize(input, part, params, &mut frames)?;
+
+        Ok(frames)
+    });
+
+    match written {
+        Ok(frames) => eprintln!(
+            "Wrote {} frames to {}",
+            frames.count(),
+            frames.dir().display()
+        ),
+        Err(err) => {
+            eprintln!("Failed to visualize: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_day(day: &str) -> u32 {
+    match day.parse() {
+        Ok(day) if (1..=25).contains(&day) => day,
+        _ => {
+            eprintln!("Day must be between 1 and 25: {}", day);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_bench(days: &[AdventOfCodeDay], matches: &ArgMatches) {
+    let day = matches.value_of("day").unwrap();
+    let part = matches.value_of("part").unwrap();
+
+    let runs: usize = match matches.value_of("runs").unwrap().parse() {
+        Ok(runs) if runs > 0 => runs,
+        _ => {
+            eprintln!("Number of runs must be a positive integer");
+            process::exit(1);
+        }
+    };
+
+    let run = match get_part(days, day, part) {
+        Ok(run) => run,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    let input = read_input(day, matches);
+    let params = read_params_arg(matches);
+
+    let mut parse_times = vec![];
+    let mut solve_times = vec![];
+    let mut total_times = vec![];
+    for i in 0..runs {
+        let result = run(&input, &params).unwrap_or_else(|err| exit_run_error(&err));
+        if i == 0 {
+            print_warnings(&result.warnings);
+        }
+
+        parse_times.push(result.parse_time);
+        solve_times.push(result.solve_time);
+        total_times.push(result.total_time());
+    }
+
+    println!("Day {} part {}, {} runs", day, part, runs);
+    println!("{:<6} {:>12} {:>12} {:>12}", "", "Min", "Median", "Max");
+    for (name, times) in [
+        ("Parse", &mut parse_times),
+        ("Solve", &mut solve_times),
+        ("Total", &mut total_times),
+    ]
+    .iter_mut()
+    {
+        let (min, median, max) = summarize_times(times);
+
+        println!(
+            "{:<6} {:>12} {:>12} {:>12}",
+            name,
+            format!("{:.2?}", min),
+            format!("{:.2?}", median),
+            format!("{:.2?}", max)
+        );
+    }
+}
+
+fn summarize_times(times: &mut [Duration]) -> (Duration, Duration, Duration) {
+    times.sort();
+
+    let mid = times.len() / 2;
+    let median = if times.len().is_multiple_of(2) {
+        (times[mid - 1] + times[mid]) / 2
+    } else {
+        times[mid]
+    };
+
+    (times[0], median, times[times.len() - 1])
+}
+
+/// The answer or error for each part of a day, along with how long it took, or the error from
+/// reading the day's input.
+type DayResults = Result<Vec<(&'static str, Result<String, String>, Duration)>, String>;
+
+fn run_all(days: &[AdventOfCodeDay], matches: &ArgMatches) -> bool {
+    let mut all_succeeded = true;
+
+    let json = matches.value_of("format") == Some("json");
+    if !json {
+        println!("{:<5} {:<5} {:<20} Time", "Day", "Part", "Answer");
+    }
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    #[cfg(feature = "parallel")]
+    {
+        let results: Vec<DayResults> = days.par_iter().map(run_day_parts).collect();
+        for (day, results) in days.iter().zip(results) {
+            all_succeeded &= print_day_results(&day.0, results, json);
+        }
+    }
+    #[cfg(not(feature = "parallel"))]
+    for day in days.iter() {
+        all_succeeded &= print_day_results(&day.0, run_day_parts(day), json);
+    }
+
+    panic::set_hook(default_hook);
+
+    all_succeeded
+}
+
+fn run_day_parts((day, part_one, part_two, _): &AdventOfCodeDay) -> DayResults {
+    let path = get_default_input_path(day, None);
+    let input = fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    let results = [("one", part_one), ("two", part_two)]
+        .iter()
+        .map(|(part, run)| {
+            let start = Instant::now();
+            let result = run_catching_panics(**run, &input);
+
+            (*part, result, start.elapsed())
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/// Prints the results of running a day, returning whether every part succeeded.
+fn print_day_results(day: &str, results: DayResults, json: bool) -> bool {
+    let results = match results {
+        Ok(results) => results,
+        Err(message) => {
+            if json {
+                println!("{}", error_json(day, None, &message));
+            } else {
+                println!("{:<5} {:<5} {}", day, "-", message);
+            }
+            return false;
+        }
+    };
+
+    let mut all_succeeded = true;
+    for (part, result, elapsed) in results {
+        if json {
+            match &result {
+                Ok(answer) => println!("{}", answer_json(day, part, answer, elapsed)),
+                Err(err) => println!("{}", error_json(day, Some(part), err)),
+            }
+        }
+
+        let answer = result.unwrap_or_else(|err| {
+            all_succeeded = false;
+            err
+        });
+        if json {
+            continue;
+        }
+
+        let mut lines = answer.lines();
+        println!(
+            "{:<5} {:<5} {:<20} {:.2?}",
+            day,
+            part,
+            lines.next().unwrap_or(""),
+            elapsed
+        );
+        for line in lines {
+            println!("{:<5} {:<5} {}", "", "", line);
+        }
+    }
+
+    all_succeeded
+}
+
+fn run_verify(days: &[AdventOfCodeDay], matches: &ArgMatches) -> bool {
+    let answers_path = Path::new(matches.value_of("answers").unwrap());
+    let answers = match read_answers(&read_file(answers_path)) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!(
+                "Failed to read answers file {}: {}",
+                answers_path.display(),
+                err
+            );
+            process::exit(1);
+        }
+    };
+
+    let only_day = matches.value_of("day");
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut num_passed = 0;
+    let mut num_failed = 0;
+    for expected in answers.iter() {
+        if only_day.map(|d| d != expected.day).unwrap_or(false) {
+            continue;
+        }
+
+        let actual = get_part(days, &expected.day, &expected.part).and_then(|run| {
+            let input = fs::read_to_string(&expected.input)
+                .map_err(|err| format!("failed to read input file: {}", err))?;
+
+            run_catching_panics(run, &input)
+        });
+
+        let description = format!(
+            "day {} part {} ({})",
+            expected.day,
+            expected.part,
+            expected.input.display()
+        );
+        match actual {
+            Ok(answer) if answer == expected.answer => {
+                num_passed += 1;
+                println!("PASS {}", description);
+            }
+            Ok(answer) => {
+                num_failed += 1;
+                println!("FAIL {}", description);
+                println!(
+                    "  expected: {}",
+                    expected.answer.replace('\n', "\n            ")
+                );
+                println!("  actual:   {}", answer.replace('\n', "\n            "));
+            }
+            Err(err) => {
+                num_failed += 1;
+                println!("FAIL {}", description);
+                println!("  {}", err);
+            }
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    println!("{} passed, {} failed", num_passed, num_failed);
+
+    num_failed == 0
+}
+
+fn answer_json(day: &str, part: &str, answer: &str, elapsed: Duration) -> String {
+    json!({
+        "day": day.parse::<u32>().unwrap(),
+        "part": part,
+        "answer": answer,
+        "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+    })
+    .to_string()
+}
+
+fn error_json(day: &str, part: Option<&str>, error: &str) -> String {
+    json!({
+        "day": day.parse::<u32>().unwrap(),
+        "part": part,
+        "error": error,
+    })
+    .to_string()
+}
+
+fn get_part(days: &[AdventOfCodeDay], day: &str, part: &str) -> Result<PartRunner, String> {
+    let (_, part_one, part_two, _) = days
+        .iter()
+        .find(|d| d.0 == day)
+        .ok_or(format!("Unknown day: {}", day))?;
+
+    match part {
+        "one" => Ok(*part_one),
+        "two" => Ok(*part_two),
+        p => Err(format!("Unknown part: {}", p)),
+    }
+}
+
+fn run_catching_panics(run: PartRunner, input: &str) -> Result<String, String> {
+    match panic::catch_unwind(|| run(input, &Params::default())) {
+        Ok(Ok(result)) => Ok(result.answer.to_string()),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(payload) => Err(format!("panicked: {}", get_panic_message(payload))),
+    }
+}
+
+fn print_warnings(warnings: &[ParseError]) {
+    for warning in warnings {
+        eprintln!("warning: skipped {}", warning);
+    }
+}
+
+fn exit_run_error(err: &RunError) -> ! {
+    eprintln!("error: {}", err);
+    process::exit(1);
+}
+
+fn get_panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn read_input(day: &str, matches: &ArgMatches) -> String {
+    if let Some(text) = matches.value_of("text") {
+        return unescape(text);
+    }
+
+    match matches.value_of("input") {
+        Some("-") => read_stdin(),
+        Some(path) => read_file(Path::new(path)),
+        None => {
+            let example = if matches.is_present("example") {
+                Some(matches.value_of("example").unwrap_or(""))
+            } else {
+                None
+            };
+
+            read_file(&get_default_input_path(day, example))
+        }
+    }
+}
+
+fn read_params_arg(matches: &ArgMatches) -> Params {
+    let args = matches.values_of("param").into_iter().flatten();
+
+    read_params(args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    })
+}
+
+fn get_default_input_path(day: &str, example: Option<&str>) -> PathBuf {
+    let file_name = match example {
+        None => format!("{}.txt", day),
+        Some("") => format!("{}_example.txt", day),
+        Some(name) => format!("{}_example_{}.txt", day, name),
+    };
+
+    PathBuf::from("inputs").join(file_name)
+}
+
+fn read_file(path: &Path) -> String {
+    match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Failed to read input file {}: {}", path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+fn read_stdin() -> String {
+    let mut input = String::new();
+    io::stdin().lock().read_to_string(&mut input).unwrap();
+
+    input
+}
+
+type AdventOfCodeDay = (String, PartRunner, PartRunner, Visualizer);
+
+fn day<S: Solution>() -> AdventOfCodeDay {
+    (
+        S::DAY.to_string(),
+        run_part_one::<S> as PartRunner,
+        run_part_two::<S> as PartRunner,
+        run_visualize::<S> as Visualizer,
+    )
+}
 
 fn get_days() -> Vec<AdventOfCodeDay> {
     vec![
-        (
-            "1".to_string(),
-            advent_of_code_2021::one::part_one as fn(),
-            advent_of_code_2021::one::part_two as fn(),
-        ),
-        (
-            "2".to_string(),
-            advent_of_code_2021::two::part_one as fn(),
-            advent_of_code_2021::two::part_two as fn(),
-        ),
-        (
-            "3".to_string(),
-            advent_of_code_2021::three::part_one as fn(),
-            advent_of_code_2021::three::part_two as fn(),
-        ),
-        (
-            "4".to_string(),
-            advent_of_code_2021::four::part_one as fn(),
-            advent_of_code_2021::four::part_two as fn(),
-        ),
-        (
-            "5".to_string(),
-            advent_of_code_2021::five::part_one as fn(),
-            advent_of_code_2021::five::part_two as fn(),
-        ),
-        (
-            "6".to_string(),
-            advent_of_code_2021::six::part_one as fn(),
-            advent_of_code_2021::six::part_two as fn(),
-        ),
-        (
-            "7".to_string(),
-            advent_of_code_2021::seven::part_one as fn(),
-            advent_of_code_2021::seven::part_two as fn(),
-        ),
-        (
-            "8".to_string(),
-            advent_of_code_2021::eight::part_one as fn(),
-            advent_of_code_2021::eight::part_two as fn(),
-        ),
-        (
-            "9".to_string(),
-            advent_of_code_2021::nine::part_one as fn(),
-            advent_of_code_2021::nine::part_two as fn(),
-        ),
-        (
-            "10".to_string(),
-            advent_of_code_2021::ten::part_one as fn(),
-            advent_of_code_2021::ten::part_two as fn(),
-        ),
-        (
-            "11".to_string(),
-            advent_of_code_2021::eleven::part_one as fn(),
-            advent_of_code_2021::eleven::part_two as fn(),
-        ),
-        (
-            "12".to_string(),
-            advent_of_code_2021::twelve::part_one as fn(),
-            advent_of_code_2021::twelve::part_two as fn(),
-        ),
-        (
-            "13".to_string(),
-            advent_of_code_2021::thirteen::part_one as fn(),
-            advent_of_code_2021::thirteen::part_two as fn(),
-        ),
-        (
-            "14".to_string(),
-            advent_of_code_2021::fourteen::part_one as fn(),
-            advent_of_code_2021::fourteen::part_two as fn(),
-        ),
-        (
-            "15".to_string(),
-            advent_of_code_2021::fifteen::part_one as fn(),
-            advent_of_code_2021::fifteen::part_two as fn(),
-        ),
-        (
-            "16".to_string(),
-            advent_of_code_2021::sixteen::part_one as fn(),
-            advent_of_code_2021::sixteen::part_two as fn(),
-        ),
-        (
-            "17".to_string(),
-            advent_of_code_2021::seventeen::part_one as fn(),
-            advent_of_code_2021::seventeen::part_two as fn(),
-        ),
-        (
-            "18".to_string(),
-            advent_of_code_2021::eighteen::part_one as fn(),
-            advent_of_code_2021::eighteen::part_two as fn(),
-        ),
-        (
-            "19".to_string(),
-            advent_of_code_2021::nineteen::part_one as fn(),
-            advent_of_code_2021::nineteen::part_two as fn(),
-        ),
-        (
-            "20".to_string(),
-            advent_of_code_2021::twenty::part_one as fn(),
-            advent_of_code_2021::twenty::part_two as fn(),
-        ),
-        (
-            "21".to_string(),
-            advent_of_code_2021::twenty_one::part_one as fn(),
-            advent_of_code_2021::twenty_one::part_two as fn(),
-        ),
+        day::<one::Solver>(),
+        day::<two::Solver>(),
+        day::<three::Solver>(),
+        day::<four::Solver>(),
+        day::<five::Solver>(),
+        day::<six::Solver>(),
+        day::<seven::Solver>(),
+        day::<eight::Solver>(),
+        day::<nine::Solver>(),
+        day::<ten::Solver>(),
+        day::<eleven::Solver>(),
+        day::<twelve::Solver>(),
+        day::<thirteen::Solver>(),
+        day::<fourteen::Solver>(),
+        day::<fifteen::Solver>(),
+        day::<sixteen::Solver>(),
+        day::<seventeen::Solver>(),
+        day::<eighteen::Solver>(),
+        day::<nineteen::Solver>(),
+        day::<twenty::Solver>(),
+        day::<twenty_one::Solver>(),
     ]
 }
@@ -1,128 +1,128 @@
-use std::collections::BTreeSet;
-use std::collections::HashSet;
-use std::io;
-use std::io::prelude::*;
+use crate::error::ParseError;
+use crate::grid::{Grid, Position};
+use crate::params::Params;
+use crate::search::flood_fill;
+use crate::solution::Solution;
+use crate::visualize::{gradient, Frames, Rgb, BLACK, PALETTE, RED, WHITE};
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = find_sum_risk_low_points(&numbers);
+pub struct Solver;
 
-    println!("{}", answer);
-}
+impl Solution for Solver {
+    const DAY: u32 = 9;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = find_basins(&numbers);
+    type Input = Grid<i64>;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-    println!("{}", answer);
-}
+    fn parse(input: &str) -> Result<Grid<i64>, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(map: &Grid<i64>) -> i64 {
+        find_sum_risk_low_points(map)
+    }
+
+    fn part_two(map: &Grid<i64>) -> i64 {
+        find_basins(map)
+    }
+
+    fn visualize(
+        map: &Grid<i64>,
+        part: &str,
+        _params: &Params,
+        frames: &mut Frames,
+    ) -> Result<(), String> {
+        let low_points: Vec<Position> = map
+            .positions()
+            .filter(|pos| is_low_point(map, *pos))
+            .collect();
+
+        if part == "one" {
+            let mut marked = map.map(|height| height_color(*height));
+            for pos in low_points.iter() {
+                marked[*pos] = RED;
+            }
 
-fn read_input() -> Vec<Vec<i64>> {
-    let stdin = io::stdin();
+            frames.write(&marked, |rgb| *rgb)?;
+            return Ok(());
+        }
 
-    let mut numbers: Vec<Vec<i64>> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<char> = line.chars().collect();
+        let mut basins = map.map(|height| if *height == 9 { BLACK } else { WHITE });
+        frames.write(&basins, |rgb| *rgb)?;
+        for (i, low_point) in low_points.iter().enumerate() {
+            for pos in get_basin(map, *low_point, 9) {
+                basins[pos] = PALETTE[i % PALETTE.len()];
+            }
 
-        numbers.push(
-            parts
-                .iter()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect(),
-        );
+            frames.write(&basins, |rgb| *rgb)?;
+        }
+
+        Ok(())
     }
+}
 
-    numbers
+fn height_color(height: i64) -> Rgb {
+    gradient([20, 60, 20], [230, 230, 200], height as f64 / 9.0)
 }
 
-fn get_neighbors(map: &[Vec<i64>], i: usize, j: usize) -> Vec<(usize, usize)> {
-    let mut neighbors: Vec<(usize, usize)> = vec![];
+fn read_input(input: &str) -> Result<Grid<i64>, ParseError> {
+    Grid::parse(input, "expected a height", |c| {
+        c.to_digit(10).map(|h| h as i64)
+    })
+}
 
-    if i > 0 {
-        neighbors.push((i - 1, j));
-    }
-    if i < map.len() - 1 {
-        neighbors.push((i + 1, j));
-    }
-    if j > 0 {
-        neighbors.push((i, j - 1));
-    }
-    if j < map[0].len() - 1 {
-        neighbors.push((i, j + 1));
-    }
+fn is_low_point(map: &Grid<i64>, (x, y): Position) -> bool {
+    map.orthogonal_neighbors(x, y)
+        .all(|neighbor| map[neighbor] > map[(x, y)])
+}
 
-    neighbors
+fn find_sum_risk_low_points(map: &Grid<i64>) -> i64 {
+    map.positions()
+        .filter(|pos| is_low_point(map, *pos))
+        .map(|pos| map[pos] + 1)
+        .sum()
 }
 
-fn find_sum_risk_low_points(map: &[Vec<i64>]) -> i64 {
-    let mut sum = 0;
-    for i in 0..map.len() {
-        for j in 0..map[i].len() {
-            let mut any_lower = false;
-            for (i2, j2) in get_neighbors(map, i, j) {
-                if map[i2][j2] <= map[i][j] {
-                    any_lower = true;
-                }
-            }
+fn get_basin(map: &Grid<i64>, low_point: Position, max: i64) -> Vec<Position> {
+    flood_fill(low_point, |&(x, y)| {
+        let value = map[(x, y)];
 
-            if !any_lower {
-                sum += map[i][j] + 1;
-            }
-        }
-    }
+        map.orthogonal_neighbors(x, y)
+            .filter(move |n| map[*n] != max && map[*n] >= value)
+    })
+}
 
-    sum
+fn get_basin_size(map: &Grid<i64>, low_point: Position, max: i64) -> i64 {
+    get_basin(map, low_point, max).len() as i64
 }
 
-fn get_basin_size(
-    map: &[Vec<i64>],
-    visited: &mut HashSet<(usize, usize)>,
-    i: usize,
-    j: usize,
-    max: i64,
-) -> i64 {
-    let value = map[i][j];
-    if value == max || visited.contains(&(i, j)) {
-        0
-    } else {
-        visited.insert((i, j));
-
-        let mut sum = 1;
-        for (i2, j2) in get_neighbors(map, i, j) {
-            let neighbor = map[i2][j2];
-            if neighbor >= value {
-                sum += get_basin_size(map, visited, i2, j2, max);
-            }
-        }
+fn find_basins(map: &Grid<i64>) -> i64 {
+    let mut basin_sizes: Vec<i64> = map
+        .positions()
+        .filter(|pos| is_low_point(map, *pos))
+        .map(|pos| get_basin_size(map, pos, 9))
+        .collect();
 
-        sum
-    }
+    basin_sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+    basin_sizes.iter().take(3).product::<i64>()
 }
 
-fn find_basins(map: &[Vec<i64>]) -> i64 {
-    let mut low_points: Vec<(usize, usize)> = vec![];
-    for i in 0..map.len() {
-        for j in 0..map[i].len() {
-            let mut any_lower = false;
-            for (i2, j2) in get_neighbors(map, i, j) {
-                if map[i2][j2] <= map[i][j] {
-                    any_lower = true;
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if !any_lower {
-                low_points.push((i, j));
-            }
-        }
-    }
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/9_example.txt")).unwrap();
 
-    let mut basin_sizes: BTreeSet<i64> = BTreeSet::new();
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-    for (i, j) in low_points {
-        let basin_size = get_basin_size(map, &mut visited, i, j, 9);
-        basin_sizes.insert(-basin_size);
+        assert_eq!(15, Solver::part_one(&input));
     }
 
-    -basin_sizes.iter().take(3).product::<i64>()
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/9_example.txt")).unwrap();
+
+        assert_eq!(1134, Solver::part_two(&input));
+    }
 }
@@ -1,262 +1,104 @@
 use std::collections::{HashMap, HashSet};
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let scanners = read_input();
-    let answer = solve_1(&scanners);
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-    println!("{}", answer);
-}
-
-pub fn part_two() {
-    let scanners = read_input();
-    let answer = solve_2(&scanners);
-
-    println!("{}", answer);
-}
-
-type ScannerId = i32;
-
-#[derive(Clone, Debug)]
-struct Scanner {
-    id: ScannerId,
-    beacons: HashSet<RelativePosition>,
-}
-
-impl Scanner {
-    fn from_lines(lines: &[String]) -> Scanner {
-        let id_str = lines[0].split("scanner ").collect::<Vec<&str>>()[1]
-            .split(' ')
-            .collect::<Vec<&str>>()[0];
-
-        let id: i32 = id_str.parse().unwrap();
-
-        let beacons: HashSet<RelativePosition> = lines[1..lines.len()]
-            .iter()
-            .map(|l| RelativePosition::from_str(l))
-            .collect();
-
-        Scanner { id, beacons }
-    }
-
-    fn rotated_beacons(&self, rotation: &Rotation) -> HashSet<RelativePosition> {
-        self.beacons.iter().map(|p| p.rotated(rotation)).collect()
-    }
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct RelativePosition {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl RelativePosition {
-    fn dist(&self, other: &RelativePosition) -> RelativeDistance {
-        RelativeDistance::new(other.x - self.x, other.y - self.y, other.z - self.z)
-    }
-
-    fn new(x: i32, y: i32, z: i32) -> RelativePosition {
-        RelativePosition { x, y, z }
-    }
-
-    fn from_str(pos_str: &str) -> RelativePosition {
-        let parts: Vec<&str> = pos_str.split(',').collect();
-
-        let x: i32 = parts[0].parse().unwrap();
-        let y: i32 = parts[1].parse().unwrap();
-        let z: i32 = parts[2].parse().unwrap();
-
-        RelativePosition { x, y, z }
-    }
-
-    fn rotated(&self, rotation: &Rotation) -> RelativePosition {
-        let new_x = self.get_axis_value(&rotation.axes[0].0) * rotation.axes[0].1.to_i32();
-        let new_y = self.get_axis_value(&rotation.axes[1].0) * rotation.axes[1].1.to_i32();
-        let new_z = self.get_axis_value(&rotation.axes[2].0) * rotation.axes[2].1.to_i32();
-
-        RelativePosition::new(new_x, new_y, new_z)
-    }
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::geometry::{Point3, Rotation3};
+use crate::parsing::{match_pattern, sections};
+use crate::solution::Solution;
 
-    fn get_axis_value(&self, axis: &Dir) -> i32 {
-        match axis {
-            Dir::X => self.x,
-            Dir::Y => self.y,
-            Dir::Z => self.z,
-        }
-    }
+pub struct Solver;
 
-    fn offsetted(&self, offset: &RelativePosition) -> RelativePosition {
-        RelativePosition::new(self.x + offset.x, self.y + offset.y, self.z + offset.z)
-    }
-}
+impl Solution for Solver {
+    const DAY: u32 = 19;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-struct RelativeDistance {
-    x: i32,
-    y: i32,
-    z: i32,
-}
+    type Input = Vec<Scanner>;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-impl RelativeDistance {
-    fn new(x: i32, y: i32, z: i32) -> RelativeDistance {
-        RelativeDistance { x, y, z }
+    fn parse(input: &str) -> Result<Vec<Scanner>, ParseError> {
+        read_input(input)
     }
 
-    fn to_pos(&self) -> RelativePosition {
-        RelativePosition::new(self.x, self.y, self.z)
+    fn part_one(scanners: &Vec<Scanner>) -> i32 {
+        solve_1(scanners)
     }
 
-    fn manhattan(&self) -> i32 {
-        self.x.abs() + self.y.abs() + self.z.abs()
+    fn part_two(scanners: &Vec<Scanner>) -> i32 {
+        solve_2(scanners)
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-enum Dir {
-    X,
-    Y,
-    Z,
-}
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-enum Sign {
-    Positive,
-    Negative,
-}
-
-impl Sign {
-    fn to_i32(self) -> i32 {
-        match self {
-            Sign::Positive => 1,
-            Sign::Negative => -1,
-        }
-    }
+type ScannerId = i32;
 
-    fn times(&self, other: &Sign) -> Sign {
-        use Sign::*;
+/// How to rotate and then shift a neighboring scanner's beacons to line them up with this scanner's.
+type Relation = (Rotation3, Point3, ScannerId);
 
-        match (self, other) {
-            (Positive, Positive) => Positive,
-            (Negative, Negative) => Positive,
-            _ => Negative,
-        }
-    }
-}
-
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct Rotation {
-    axes: [(Dir, Sign); 3],
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    id: ScannerId,
+    beacons: HashSet<Point3>,
 }
 
-impl Rotation {
-    fn rotated(&self, other: &Rotation) -> Rotation {
-        let mut axes = [(Dir::X, Sign::Positive); 3];
-
-        let mut new_x = self.get_axis_value(&other.axes[0].0);
-        new_x.1 = new_x.1.times(&other.axes[0].1);
-
-        let mut new_y = self.get_axis_value(&other.axes[1].0);
-        new_y.1 = new_y.1.times(&other.axes[1].1);
-
-        let mut new_z = self.get_axis_value(&other.axes[2].0);
-        new_z.1 = new_z.1.times(&other.axes[2].1);
+impl Scanner {
+    fn from_lines(lines: &[SourceLine]) -> Result<Scanner, ParseError> {
+        let header = &lines[0];
+        let parts = match_pattern(
+            header,
+            "--- scanner {} ---",
+            "expected a header of the form --- scanner N ---",
+        )?;
 
-        axes[0] = new_x;
-        axes[1] = new_y;
-        axes[2] = new_z;
+        let id: i32 = header.parse(parts[0], "expected a scanner id")?;
 
-        Rotation { axes }
-    }
+        let beacons: HashSet<Point3> = lines[1..lines.len()]
+            .iter()
+            .map(|line| Point3::from_str(line, line.text))
+            .collect::<Result<_, _>>()?;
 
-    fn get_axis_value(&self, axis: &Dir) -> (Dir, Sign) {
-        match axis {
-            Dir::X => self.axes[0],
-            Dir::Y => self.axes[1],
-            Dir::Z => self.axes[2],
-        }
+        Ok(Scanner { id, beacons })
     }
 
-    fn all() -> Vec<Rotation> {
-        let signs = vec![Sign::Positive, Sign::Negative];
-
-        let mut rotations = vec![];
-        for x_pos in 0..3 {
-            for y_pos in 0..3 {
-                if y_pos == x_pos {
-                    continue;
-                }
-
-                for z_pos in 0..3 {
-                    if z_pos == x_pos || z_pos == y_pos {
-                        continue;
-                    }
-
-                    for x_sign in signs.iter() {
-                        for y_sign in signs.iter() {
-                            for z_sign in signs.iter() {
-                                let mut axes = [(Dir::X, Sign::Positive); 3];
-
-                                axes[x_pos] = (Dir::X, *x_sign);
-                                axes[y_pos] = (Dir::Y, *y_sign);
-                                axes[z_pos] = (Dir::Z, *z_sign);
-
-                                rotations.push(Rotation { axes });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        rotations
+    fn rotated_beacons(&self, rotation: &Rotation3) -> HashSet<Point3> {
+        self.beacons.iter().map(|p| p.rotated(rotation)).collect()
     }
 }
 
-fn read_input() -> Vec<Scanner> {
-    let stdin = io::stdin();
-
-    let mut scanners: Vec<Scanner> = Vec::new();
-    let mut scanner_lines: Vec<String> = vec![];
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
+fn read_input(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    let scanners: Vec<Scanner> = sections(input)
+        .iter()
+        .map(|lines| Scanner::from_lines(lines))
+        .collect::<Result<_, _>>()?;
 
-        if line.is_empty() {
-            scanners.push(Scanner::from_lines(&scanner_lines));
-            scanner_lines = vec![];
-        } else {
-            scanner_lines.push(line.to_string());
-        }
+    if scanners.is_empty() {
+        return Err(missing_line(1, "expected a scanner"));
     }
 
-    scanners.push(Scanner::from_lines(&scanner_lines));
-    scanner_lines.clear();
-
-    scanners
+    Ok(scanners)
 }
 
 fn calc_beacon_neighbors(
-    beacons: &HashSet<RelativePosition>,
+    beacons: &HashSet<Point3>,
     num_neighbors: i32,
-) -> HashMap<RelativePosition, Vec<RelativeDistance>> {
-    let mut beacon_neighbors: HashMap<RelativePosition, Vec<RelativeDistance>> = HashMap::new();
+) -> HashMap<Point3, Vec<Point3>> {
+    let mut beacon_neighbors: HashMap<Point3, Vec<Point3>> = HashMap::new();
 
     for b_1 in beacons.iter() {
         let mut neighbors = beacons
             .iter()
             .filter(|b_2| b_1 != *b_2)
-            .map(|b_2| b_1.dist(b_2))
-            .collect::<Vec<RelativeDistance>>();
+            .map(|b_2| *b_2 - *b_1)
+            .collect::<Vec<Point3>>();
 
-        neighbors.sort_by_key(|dist| dist.manhattan());
+        neighbors.sort_by_key(|dist| dist.manhattan(&Point3::ORIGIN));
 
         beacon_neighbors.insert(
-            b_1.clone(),
+            *b_1,
             neighbors
                 .iter()
-                .cloned()
                 .take(num_neighbors as usize)
+                .cloned()
                 .collect(),
         );
     }
@@ -264,10 +106,7 @@ fn calc_beacon_neighbors(
     beacon_neighbors
 }
 
-fn find_potential_offsets(
-    beacons_1: &HashSet<RelativePosition>,
-    beacons_2: &HashSet<RelativePosition>,
-) -> Vec<RelativePosition> {
+fn find_potential_offsets(beacons_1: &HashSet<Point3>, beacons_2: &HashSet<Point3>) -> Vec<Point3> {
     let beacon_neighbors_1 = calc_beacon_neighbors(beacons_1, 3);
     let beacon_neighbors_2 = calc_beacon_neighbors(beacons_2, 3);
 
@@ -275,7 +114,7 @@ fn find_potential_offsets(
     for (b_1, neighbors_1) in beacon_neighbors_1.iter() {
         for (b_2, neighbors_2) in beacon_neighbors_2.iter() {
             if neighbors_1 == neighbors_2 {
-                let offset = b_2.dist(b_1).to_pos();
+                let offset = *b_1 - *b_2;
 
                 possible_offsets.push(offset);
             }
@@ -286,14 +125,14 @@ fn find_potential_offsets(
 }
 
 fn find_best_offset(
-    beacons_1: &HashSet<RelativePosition>,
-    beacons_2: &HashSet<RelativePosition>,
-) -> Option<(RelativePosition, i32)> {
+    beacons_1: &HashSet<Point3>,
+    beacons_2: &HashSet<Point3>,
+) -> Option<(Point3, i32)> {
     for offset in find_potential_offsets(beacons_1, beacons_2) {
         let mut num_matches = 0;
         for b_1 in beacons_1.iter() {
             for b_2 in beacons_2.iter() {
-                let b_2 = b_2.offsetted(&offset);
+                let b_2 = *b_2 + offset;
 
                 if *b_1 == b_2 {
                     num_matches += 1;
@@ -309,26 +148,18 @@ fn find_best_offset(
     None
 }
 
-fn find_relations(
-    scanners: &[Scanner],
-) -> HashMap<ScannerId, Vec<(Rotation, RelativePosition, ScannerId)>> {
-    let mut relations = HashMap::new();
-
-    for scanner_1 in scanners.iter() {
-        for scanner_2 in scanners.iter() {
-            if scanner_1.id == scanner_2.id {
-                continue;
-            }
+fn find_scanner_relations(scanner_1: &Scanner, scanners: &[Scanner]) -> Vec<Relation> {
+    let mut relations = vec![];
+    for scanner_2 in scanners.iter() {
+        if scanner_1.id == scanner_2.id {
+            continue;
+        }
 
-            for r_2 in Rotation::all().iter() {
-                let beacons_2 = scanner_2.rotated_beacons(r_2);
+        for r_2 in Rotation3::all().iter() {
+            let beacons_2 = scanner_2.rotated_beacons(r_2);
 
-                if let Some((shift, _)) = find_best_offset(&scanner_1.beacons, &beacons_2) {
-                    relations
-                        .entry(scanner_1.id)
-                        .or_insert_with(Vec::new)
-                        .push((*r_2, shift, scanner_2.id));
-                }
+            if let Some((shift, _)) = find_best_offset(&scanner_1.beacons, &beacons_2) {
+                relations.push((*r_2, shift, scanner_2.id));
             }
         }
     }
@@ -336,12 +167,28 @@ fn find_relations(
     relations
 }
 
+fn find_relations(scanners: &[Scanner]) -> HashMap<ScannerId, Vec<Relation>> {
+    #[cfg(feature = "parallel")]
+    let firsts = scanners.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let firsts = scanners.iter();
+
+    let relations: Vec<(ScannerId, Vec<Relation>)> = firsts
+        .map(|scanner_1| (scanner_1.id, find_scanner_relations(scanner_1, scanners)))
+        .collect();
+
+    relations
+        .into_iter()
+        .filter(|(_, related)| !related.is_empty())
+        .collect()
+}
+
 fn get_beacons(
     src: ScannerId,
     visited: &HashSet<ScannerId>,
-    relations: &HashMap<ScannerId, Vec<(Rotation, RelativePosition, ScannerId)>>,
+    relations: &HashMap<ScannerId, Vec<Relation>>,
     scanners: &HashMap<ScannerId, Scanner>,
-) -> HashSet<RelativePosition> {
+) -> HashSet<Point3> {
     let mut beacons = scanners[&src].beacons.clone();
     for (rotation, offset, neighbor) in relations[&src].iter() {
         if visited.contains(neighbor) {
@@ -354,7 +201,7 @@ fn get_beacons(
         let neighbor_beacons = get_beacons(*neighbor, &new_visited, relations, scanners);
 
         for b in neighbor_beacons {
-            let b = b.rotated(rotation).offsetted(offset);
+            let b = b.rotated(rotation) + *offset;
 
             beacons.insert(b);
         }
@@ -365,7 +212,7 @@ fn get_beacons(
 
 fn build_complete_map(
     scanners: &[Scanner],
-    relations: &HashMap<ScannerId, Vec<(Rotation, RelativePosition, ScannerId)>>,
+    relations: &HashMap<ScannerId, Vec<Relation>>,
 ) -> Scanner {
     let mut visited = HashSet::new();
     visited.insert(0);
@@ -377,7 +224,7 @@ fn build_complete_map(
 
     let all_beacons = get_beacons(0, &visited, relations, &scanners_map);
 
-    let mut all_beacons_vec: Vec<RelativePosition> = all_beacons.iter().cloned().collect();
+    let mut all_beacons_vec: Vec<Point3> = all_beacons.iter().cloned().collect();
     all_beacons_vec.sort_by_key(|p| (p.x, p.y, p.z));
 
     Scanner {
@@ -395,23 +242,23 @@ fn solve_1(scanners: &[Scanner]) -> i32 {
 
 fn get_scanner_positions(
     src: ScannerId,
-    src_pos: &RelativePosition,
-    src_rotation: &Rotation,
-    relations: &HashMap<ScannerId, Vec<(Rotation, RelativePosition, ScannerId)>>,
-    scanner_positions: &mut HashMap<ScannerId, RelativePosition>,
+    src_pos: &Point3,
+    src_rotation: &Rotation3,
+    relations: &HashMap<ScannerId, Vec<Relation>>,
+    scanner_positions: &mut HashMap<ScannerId, Point3>,
 ) {
     for (rotation, offset, neighbor) in relations[&src].iter() {
         if scanner_positions.contains_key(neighbor) {
             continue;
         }
 
-        let new_pos = src_pos.offsetted(&offset.rotated(src_rotation));
-        scanner_positions.insert(*neighbor, new_pos.clone());
+        let new_pos = *src_pos + offset.rotated(src_rotation);
+        scanner_positions.insert(*neighbor, new_pos);
 
         get_scanner_positions(
             *neighbor,
             &new_pos,
-            &rotation.rotated(src_rotation),
+            &rotation.then(src_rotation),
             relations,
             scanner_positions,
         );
@@ -419,13 +266,13 @@ fn get_scanner_positions(
 }
 
 fn find_scanner_positions(
-    relations: &HashMap<ScannerId, Vec<(Rotation, RelativePosition, ScannerId)>>,
-) -> HashMap<ScannerId, RelativePosition> {
-    let mut scanner_positions: HashMap<ScannerId, RelativePosition> = HashMap::new();
+    relations: &HashMap<ScannerId, Vec<Relation>>,
+) -> HashMap<ScannerId, Point3> {
+    let mut scanner_positions: HashMap<ScannerId, Point3> = HashMap::new();
     get_scanner_positions(
         0,
-        &RelativePosition::new(0, 0, 0),
-        &Rotation::all()[0],
+        &Point3::ORIGIN,
+        &Rotation3::IDENTITY,
         relations,
         &mut scanner_positions,
     );
@@ -436,8 +283,7 @@ fn find_scanner_positions(
 fn solve_2(scanners: &[Scanner]) -> i32 {
     let scanner_relations = find_relations(scanners);
 
-    let scanner_positions: HashMap<ScannerId, RelativePosition> =
-        find_scanner_positions(&scanner_relations);
+    let scanner_positions: HashMap<ScannerId, Point3> = find_scanner_positions(&scanner_relations);
 
     let mut largest_dist = 0;
     for (id_1, pos_1) in scanner_positions.iter() {
@@ -446,7 +292,7 @@ fn solve_2(scanners: &[Scanner]) -> i32 {
                 continue;
             }
 
-            let dist = pos_1.dist(pos_2).manhattan();
+            let dist = pos_1.manhattan(pos_2);
             if dist > largest_dist {
                 largest_dist = dist;
             }
@@ -455,3 +301,22 @@ fn solve_2(scanners: &[Scanner]) -> i32 {
 
     largest_dist
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/19_example.txt")).unwrap();
+
+        assert_eq!(79, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/19_example.txt")).unwrap();
+
+        assert_eq!(3621, Solver::part_two(&input));
+    }
+}
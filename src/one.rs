@@ -1,65 +1,374 @@
-use std::io;
-use std::io::prelude::*;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::str::FromStr;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = count_num_increases(&numbers);
+use crate::error::{ParseError, SourceLine};
+use crate::params::Params;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = count_num_3_sum_increases(&numbers);
+impl Solution for Solver {
+    const DAY: u32 = 1;
+    const PARAMS: &'static [&'static str] = &["window", "aggregate"];
 
-    println!("{}", answer);
-}
+    type Input = Vec<i64>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-fn read_input() -> Vec<i32> {
-    let stdin = io::stdin();
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        depths(input).collect()
+    }
 
-    let mut numbers: Vec<i32> = Vec::new();
-    for line in stdin.lock().lines() {
-        let number: i32 = line.unwrap().parse().unwrap();
+    fn part_one(numbers: &Vec<i64>) -> usize {
+        Self::part_one_with(numbers, &Params::default()).unwrap()
+    }
 
-        numbers.push(number);
+    fn part_two(numbers: &Vec<i64>) -> usize {
+        Self::part_two_with(numbers, &Params::default()).unwrap()
     }
 
-    numbers
+    fn part_one_with(numbers: &Vec<i64>, params: &Params) -> Result<usize, String> {
+        let series = WindowedSeries::from_params(params, 1)?;
+
+        Ok(series.analyze(numbers.iter().copied()).increases)
+    }
+
+    fn part_two_with(numbers: &Vec<i64>, params: &Params) -> Result<usize, String> {
+        let series = WindowedSeries::from_params(params, 3)?;
+
+        Ok(series.analyze(numbers.iter().copied()).increases)
+    }
+}
+
+/// How the depths in a window are combined before the window is compared with the one before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
 }
 
-fn count_num_increases(numbers: &[i32]) -> i32 {
-    let mut prev = numbers.first().unwrap();
-    let mut num_increases = 0;
-    for num in numbers.iter() {
-        if num > prev {
-            num_increases += 1;
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Aggregate, String> {
+        match text {
+            "sum" => Ok(Aggregate::Sum),
+            "mean" => Ok(Aggregate::Mean),
+            "min" => Ok(Aggregate::Min),
+            "max" => Ok(Aggregate::Max),
+            _ => Err(format!(
+                "unknown aggregate {}, expected one of: sum, mean, min, max",
+                text
+            )),
         }
-        prev = num;
     }
+}
 
-    num_increases
+/// How each full window of a series compares with the window before it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SeriesStats {
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    /// The most windows in a row that each rose, or each fell, from the window before.
+    pub longest_monotonic_run: usize,
 }
 
-fn count_num_3_sum_increases(numbers: &[i32]) -> i32 {
-    let mut prev_sum: Option<i32> = None;
-    let mut num_increases = 0;
+/// Compares windows of `size` consecutive depths as they are pushed one at a time, so a series
+/// never has to be held in memory all at once.
+pub struct WindowedSeries {
+    size: usize,
+    aggregate: Aggregate,
+    /// A ring buffer of the last `size` depths, where `next` is the oldest once it is full.
+    window: Vec<i64>,
+    next: usize,
+    sum: i128,
+    /// The positions and depths that could still become the window's min or max, in the order
+    /// they were pushed, so the front is always the current one.
+    extremes: VecDeque<(usize, i64)>,
+    pushed: usize,
+    previous: Option<i128>,
+    run: usize,
+    run_direction: Ordering,
+    stats: SeriesStats,
+}
+
+impl WindowedSeries {
+    pub fn new(size: usize, aggregate: Aggregate) -> Result<WindowedSeries, String> {
+        if size < 1 {
+            return Err("window must be at least 1".to_string());
+        }
+
+        Ok(WindowedSeries {
+            size,
+            aggregate,
+            window: Vec::with_capacity(size),
+            next: 0,
+            sum: 0,
+            extremes: VecDeque::new(),
+            pushed: 0,
+            previous: None,
+            run: 0,
+            run_direction: Ordering::Equal,
+            stats: SeriesStats::default(),
+        })
+    }
+
+    fn from_params(params: &Params, size: usize) -> Result<WindowedSeries, String> {
+        let size = params.get("window", size)?;
+        let aggregate = params.get("aggregate", Aggregate::Sum)?;
+
+        WindowedSeries::new(size, aggregate)
+    }
+
+    pub fn push(&mut self, value: i64) {
+        if self.window.len() < self.size {
+            self.window.push(value);
+        } else {
+            self.sum -= self.window[self.next] as i128;
+            self.window[self.next] = value;
+            self.next = (self.next + 1) % self.size;
+        }
+        self.sum += value as i128;
+        self.push_extreme(value);
 
-    for i in 0..numbers.len() - 2 {
-        let a = numbers[i];
-        let b = numbers[i + 1];
-        let c = numbers[i + 2];
+        if self.window.len() == self.size {
+            let current = self.current();
+            self.compare(current);
+        }
+    }
 
-        let sum = a + b + c;
+    /// Drops any candidates that `value` outranks or that have left the window, so that each depth
+    /// is added and removed at most once.
+    fn push_extreme(&mut self, value: i64) {
+        let outranks = match self.aggregate {
+            Aggregate::Min => |back: i64, value: i64| back >= value,
+            Aggregate::Max => |back: i64, value: i64| back <= value,
+            Aggregate::Sum | Aggregate::Mean => return,
+        };
 
-        if let Some(prev) = prev_sum {
-            if sum > prev {
-                num_increases += 1;
+        while let Some(&(_, back)) = self.extremes.back() {
+            if !outranks(back, value) {
+                break;
             }
+            self.extremes.pop_back();
+        }
+        self.extremes.push_back((self.pushed, value));
+        self.pushed += 1;
+
+        while let Some(&(position, _)) = self.extremes.front() {
+            if position + self.size >= self.pushed {
+                break;
+            }
+            self.extremes.pop_front();
+        }
+    }
+
+    /// Returns a value for the current window that orders windows the same way as the aggregate.
+    fn current(&self) -> i128 {
+        match self.aggregate {
+            // Every window holds the same number of depths, so their means are ordered by their sums.
+            Aggregate::Sum | Aggregate::Mean => self.sum,
+            Aggregate::Min | Aggregate::Max => self.extremes.front().unwrap().1 as i128,
+        }
+    }
+
+    fn compare(&mut self, current: i128) {
+        let direction = match self.previous {
+            Some(previous) => current.cmp(&previous),
+            None => {
+                self.previous = Some(current);
+                self.run = 1;
+                self.stats.longest_monotonic_run = 1;
+                return;
+            }
+        };
+
+        match direction {
+            Ordering::Greater => self.stats.increases += 1,
+            Ordering::Less => self.stats.decreases += 1,
+            Ordering::Equal => self.stats.plateaus += 1,
+        }
+
+        self.run = if direction == Ordering::Equal {
+            1
+        } else if direction == self.run_direction {
+            self.run + 1
+        } else {
+            2
+        };
+        self.run_direction = direction;
+        self.stats.longest_monotonic_run = self.stats.longest_monotonic_run.max(self.run);
+        self.previous = Some(current);
+    }
+
+    pub fn stats(&self) -> SeriesStats {
+        self.stats
+    }
+
+    /// Pushes every depth in `values` and returns the stats for the whole series.
+    pub fn analyze<I>(mut self, values: I) -> SeriesStats
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        for value in values {
+            self.push(value);
         }
 
-        prev_sum = Some(sum)
+        self.stats
     }
 
-    num_increases
+    /// Pushes every depth in `values` until one fails to be read, returning its error.
+    pub fn try_analyze<I, E>(mut self, values: I) -> Result<SeriesStats, E>
+    where
+        I: IntoIterator<Item = Result<i64, E>>,
+    {
+        for value in values {
+            self.push(value?);
+        }
+
+        Ok(self.stats)
+    }
+}
+
+/// Parses the depths one line at a time, so they can be analyzed without collecting them first.
+pub fn depths(input: &str) -> impl Iterator<Item = Result<i64, ParseError>> + '_ {
+    SourceLine::all(input).map(|line| line.parse(line.text, "expected a depth"))
+}
+
+/// Reads and parses the depths one line at a time, so that a report of any size can be analyzed
+/// while only holding one line in memory.
+pub fn depths_from_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<i64, ParseError>> {
+    reader.lines().enumerate().map(|(i, text)| {
+        let text = text.map_err(|err| {
+            ParseError::new(i + 1, 1, "", &format!("failed to read line: {}", err))
+        })?;
+        let line = SourceLine {
+            number: i + 1,
+            text: &text,
+        };
+
+        line.parse(line.text, "expected a depth")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::read_params;
+
+    const EXAMPLE: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(7, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(5, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn windowed_stats() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+        let analyze = |size, aggregate| {
+            WindowedSeries::new(size, aggregate)
+                .unwrap()
+                .analyze(input.iter().copied())
+        };
+
+        let stats = analyze(1, Aggregate::Sum);
+        assert_eq!(7, stats.increases);
+        assert_eq!(2, stats.decreases);
+        assert_eq!(0, stats.plateaus);
+        assert_eq!(4, stats.longest_monotonic_run);
+
+        assert_eq!(analyze(3, Aggregate::Sum), analyze(3, Aggregate::Mean));
+
+        let stats = analyze(2, Aggregate::Min);
+        assert_eq!(
+            (5, 1, 2),
+            (stats.increases, stats.decreases, stats.plateaus)
+        );
+
+        assert!(WindowedSeries::new(0, Aggregate::Max).is_err());
+    }
+
+    #[test]
+    fn short_inputs() {
+        for input in ["", "199\n", "199\n200\n"] {
+            let input = Solver::parse(input).unwrap();
+
+            assert_eq!(0, Solver::part_two(&input));
+        }
+
+        assert_eq!(1, Solver::part_one(&Solver::parse("199\n200\n").unwrap()));
+    }
+
+    #[test]
+    fn streamed_depths() {
+        let input = "9000000000000000000\n9000000000000000001\n9000000000000000001\n1\n";
+        let stats = WindowedSeries::new(2, Aggregate::Sum)
+            .unwrap()
+            .analyze(depths(input).map(Result::unwrap));
+
+        assert_eq!(
+            (1, 1, 0),
+            (stats.increases, stats.decreases, stats.plateaus)
+        );
+        assert!(depths("12\nfoo\n").nth(1).unwrap().is_err());
+        assert_eq!(2, Solver::parse("12\nfoo\n").err().unwrap().line);
+
+        let stats = WindowedSeries::new(3, Aggregate::Sum)
+            .unwrap()
+            .try_analyze(depths_from_reader(EXAMPLE.as_bytes()))
+            .unwrap();
+        assert_eq!(5, stats.increases);
+
+        let err = WindowedSeries::new(1, Aggregate::Sum)
+            .unwrap()
+            .try_analyze(depths_from_reader("1\n2\nx\n".as_bytes()))
+            .unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn extremes_match_rescan() {
+        let depths: Vec<i64> = (0..200).map(|i| (i * 37 % 23) - (i % 7) * 3).collect();
+
+        for size in 1..8 {
+            for &aggregate in [Aggregate::Min, Aggregate::Max].iter() {
+                let mut series = WindowedSeries::new(size, aggregate).unwrap();
+                for (i, depth) in depths.iter().enumerate() {
+                    series.push(*depth);
+                    if i + 1 >= size {
+                        let window = depths[(i + 1 - size)..=i].iter();
+                        let expected = match aggregate {
+                            Aggregate::Min => window.min(),
+                            _ => window.max(),
+                        };
+                        assert_eq!(*expected.unwrap() as i128, series.current());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn window_params() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+        let params = read_params(vec!["window=2", "aggregate=max"]).unwrap();
+
+        assert_eq!(Ok(4), Solver::part_two_with(&input, &params));
+        assert!("median".parse::<Aggregate>().is_err());
+    }
 }
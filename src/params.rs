@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Named values that adjust how a puzzle is solved, such as how many steps to run, given on the
+/// command line as `name=value`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    /// Gets the value of a parameter, or `default` if it was not given.
+    pub fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.values.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value for parameter {}: {}", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    /// Checks that every parameter given is one of `names`.
+    pub fn check_names(&self, names: &[&str]) -> Result<(), String> {
+        for name in self.values.keys() {
+            if !names.contains(&name.as_str()) {
+                return Err(if names.is_empty() {
+                    format!("unknown parameter {}, this day takes no parameters", name)
+                } else {
+                    format!(
+                        "unknown parameter {}, expected one of: {}",
+                        name,
+                        names.join(", ")
+                    )
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads parameters from arguments of the form `name=value`.
+pub fn read_params<'a, I>(args: I) -> Result<Params, String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut values = BTreeMap::new();
+    for arg in args {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) if !name.is_empty() => (name, value),
+            _ => {
+                return Err(format!(
+                    "expected a parameter of the form name=value: {}",
+                    arg
+                ))
+            }
+        };
+
+        if values.insert(name.to_string(), value.to_string()).is_some() {
+            return Err(format!("parameter given more than once: {}", name));
+        }
+    }
+
+    Ok(Params { values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_with_defaults() {
+        let params = read_params(vec!["days=512", "name=x=y"]).unwrap();
+
+        assert_eq!(Ok(512), params.get("days", 80));
+        assert_eq!(Ok(40), params.get("steps", 40));
+        assert_eq!(Ok("x=y".to_string()), params.get("name", String::new()));
+        assert!(params.get::<u32>("name", 0).is_err());
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert!(read_params(vec!["days"]).is_err());
+        assert!(read_params(vec!["=3"]).is_err());
+        assert!(read_params(vec!["days=1", "days=2"]).is_err());
+
+        let params = read_params(vec!["days=1"]).unwrap();
+        assert!(params.check_names(&["days", "steps"]).is_ok());
+        assert!(params.check_names(&["steps"]).is_err());
+        assert!(params.check_names(&[]).is_err());
+        assert!(Params::default().check_names(&[]).is_ok());
+    }
+}
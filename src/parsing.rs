@@ -0,0 +1,239 @@
+use std::str::FromStr;
+
+use crate::error::{ParseError, SourceLine};
+
+/// Splits input into groups of lines separated by blank lines, leaving out empty groups.
+pub fn sections(input: &str) -> Vec<Vec<SourceLine<'_>>> {
+    let mut sections: Vec<Vec<SourceLine>> = vec![];
+    let mut current: Vec<SourceLine> = vec![];
+    for line in SourceLine::all(input) {
+        if line.text.trim().is_empty() {
+            if !current.is_empty() {
+                sections.push(current);
+                current = vec![];
+            }
+        } else {
+            current.push(line);
+        }
+    }
+
+    if !current.is_empty() {
+        sections.push(current);
+    }
+
+    sections
+}
+
+/// Parses a comma separated list of values from `part`, which should be a slice of `line`,
+/// reporting `message` for any value that is invalid.
+pub fn parse_comma_list<T: FromStr>(
+    line: &SourceLine,
+    part: &str,
+    message: &str,
+) -> Result<Vec<T>, ParseError> {
+    part.split(',').map(|v| line.parse(v, message)).collect()
+}
+
+/// Parses a whitespace separated list of values from `part`, which should be a slice of `line`,
+/// reporting `message` for any value that is invalid.
+pub fn parse_whitespace_list<T: FromStr>(
+    line: &SourceLine,
+    part: &str,
+    message: &str,
+) -> Result<Vec<T>, ParseError> {
+    part.split_whitespace()
+        .map(|v| line.parse(v, message))
+        .collect()
+}
+
+/// Finds every integer in a line, treating a `-` directly before digits as a minus sign, and
+/// reporting `message` for any that does not fit in an `i64`.
+pub fn extract_ints(line: &SourceLine, message: &str) -> Result<Vec<i64>, ParseError> {
+    let text = line.text;
+    let mut ints = vec![];
+    let mut start: Option<usize> = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        let is_digit = c.is_ascii_digit();
+        let is_sign = c == '-' && text[i + 1..].starts_with(|c: char| c.is_ascii_digit());
+
+        match start {
+            None if is_digit || is_sign => start = Some(i),
+            Some(s) if !is_digit => {
+                ints.push(line.parse(&text[s..i], message)?);
+                start = if is_sign { Some(i) } else { None };
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ints)
+}
+
+/// Replaces the escape sequences `\n`, `\t`, and `\\` with the characters they stand for, so that
+/// input with several lines can be given as a single command line argument.
+pub fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Matches a line against `pattern`, where each `{}` captures the text up to the next literal part
+/// of the pattern, or the rest of the line if it comes last. Returns the captured slices of the
+/// line, or an error with `message` pointing at where the line stopped matching.
+pub fn match_pattern<'a>(
+    line: &SourceLine<'a>,
+    pattern: &str,
+    message: &str,
+) -> Result<Vec<&'a str>, ParseError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+
+    let mut rest: &'a str = line.text;
+    let mut captures = vec![];
+    for (i, literal) in literals.iter().enumerate() {
+        if i > 0 {
+            let end = if literal.is_empty() {
+                if i == literals.len() - 1 {
+                    rest.len()
+                } else {
+                    return Err(line.error(rest, message));
+                }
+            } else {
+                match rest.find(literal) {
+                    Some(end) => end,
+                    None => return Err(line.error(rest, message)),
+                }
+            };
+
+            captures.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+
+        match rest.strip_prefix(literal) {
+            Some(after) => rest = after,
+            None => return Err(line.error(rest, message)),
+        }
+    }
+
+    if !rest.is_empty() {
+        return Err(line.error(rest, message));
+    }
+
+    Ok(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> SourceLine<'_> {
+        SourceLine { number: 1, text }
+    }
+
+    #[test]
+    fn sections_split_on_blank_lines() {
+        let sections = sections("a\nb\n\n\nc\n\n");
+        let numbers: Vec<Vec<usize>> = sections
+            .iter()
+            .map(|s| s.iter().map(|l| l.number).collect())
+            .collect();
+
+        assert_eq!(vec![vec![1, 2], vec![5]], numbers);
+    }
+
+    #[test]
+    fn lists() {
+        let l = line("3,4,x");
+        assert_eq!(
+            vec![3, 4],
+            parse_comma_list::<i32>(&l, &l.text[..3], "expected a number").unwrap()
+        );
+        assert_eq!(
+            5,
+            parse_comma_list::<i32>(&l, l.text, "expected a number")
+                .unwrap_err()
+                .column
+        );
+
+        let l = line(" 1  -2 3");
+        assert_eq!(
+            vec![1, -2, 3],
+            parse_whitespace_list::<i32>(&l, l.text, "expected a number").unwrap()
+        );
+    }
+
+    #[test]
+    fn extract_signed_ints() {
+        let extract = |text| {
+            let line = SourceLine { number: 1, text };
+            extract_ints(&line, "expected a number")
+        };
+
+        assert_eq!(
+            Ok(vec![20, 30, -10, -5]),
+            extract("target area: x=20..30, y=-10..-5")
+        );
+        assert_eq!(Ok(vec![1, -2, -3]), extract("a1b-2 --3-"));
+        assert_eq!(Ok(Vec::<i64>::new()), extract("no numbers - here"));
+
+        let err = extract("x=1, y=99999999999999999999").unwrap_err();
+        assert_eq!(8, err.column);
+    }
+
+    #[test]
+    fn unescapes_text() {
+        assert_eq!("199\n200\n208", unescape("199\\n200\\n208"));
+        assert_eq!("a\tb\\n\\x\\", unescape("a\\tb\\\\n\\x\\"));
+        assert_eq!("1\n2", unescape("1\n2"));
+    }
+
+    #[test]
+    fn patterns() {
+        let l = line("fold along x=12");
+        assert_eq!(
+            vec!["x", "12"],
+            match_pattern(&l, "fold along {}={}", "expected a fold").unwrap()
+        );
+
+        let l = line("0,9 -> 5,9");
+        assert_eq!(
+            vec!["0,9", "5,9"],
+            match_pattern(&l, "{} -> {}", "expected a line").unwrap()
+        );
+
+        let l = line("--- scanner 3 ---");
+        assert_eq!(
+            vec!["3"],
+            match_pattern(&l, "--- scanner {} ---", "expected a header").unwrap()
+        );
+
+        let err = match_pattern(&line("fold along x"), "fold along {}={}", "expected a fold");
+        assert_eq!(12, err.unwrap_err().column);
+
+        let err = match_pattern(&line("ab -> c -> d"), "{} -> {}", "expected a rule").unwrap();
+        assert_eq!(vec!["ab", "c -> d"], err);
+
+        assert!(match_pattern(&line("x=1!"), "x={}", "expected x").is_ok());
+        assert!(match_pattern(&line("x=1, y=2"), "x={}, y={} ", "expected x").is_err());
+    }
+}
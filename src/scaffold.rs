@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.template");
+
+const ONES: [&str; 10] = [
+    "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+/// Returns the name of the module for a day, such as `twenty_two` for day 22.
+pub fn get_module_name(day: u32) -> Result<String, String> {
+    match day {
+        1..=9 => Ok(ONES[day as usize].to_string()),
+        10..=19 => Ok(TEENS[day as usize - 10].to_string()),
+        20 => Ok("twenty".to_string()),
+        21..=25 => Ok(format!("twenty_{}", ONES[day as usize - 20])),
+        _ => Err(format!("Day must be between 1 and 25: {}", day)),
+    }
+}
+
+/// Fills in the day module template for a day.
+pub fn get_module_source(day: u32) -> String {
+    TEMPLATE.replace("{{day}}", &day.to_string())
+}
+
+/// Adds a `pub mod` line for a module to the contents of `lib.rs`, keeping the list sorted.
+pub fn register_module(lib_rs: &str, module: &str) -> Result<String, String> {
+    let new_line = format!("pub mod {};", module);
+
+    let mut lines: Vec<&str> = lib_rs.lines().collect();
+    if lines.contains(&new_line.as_str()) {
+        return Err(format!("{} is already registered in lib.rs", module));
+    }
+
+    let position = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod ") && *line > new_line.as_str())
+        .unwrap_or(lines.len());
+    lines.insert(position, &new_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds a module's solver to the end of the list returned by `get_days()` in `main.rs`.
+pub fn register_day(main_rs: &str, module: &str) -> Result<String, String> {
+    let entry = format!("day::<{}::Solver>(),", module);
+    if main_rs.contains(&entry) {
+        return Err(format!("{} is already registered in main.rs", module));
+    }
+
+    let start = main_rs
+        .find("fn get_days()")
+        .ok_or("could not find get_days() in main.rs")?;
+    let end = main_rs[start..]
+        .find("\n    ]")
+        .map(|i| start + i)
+        .ok_or("could not find the end of get_days() in main.rs")?;
+
+    Ok(format!(
+        "{}\n        {}{}",
+        &main_rs[..end],
+        entry,
+        &main_rs[end..]
+    ))
+}
+
+/// Creates the module for a new day under `root`, registers it, and creates its empty input
+/// files, returning the paths that were written.
+pub fn create_day(root: &Path, day: u32) -> Result<Vec<PathBuf>, String> {
+    let module = get_module_name(day)?;
+
+    let module_path = root.join("src").join(format!("{}.rs", module));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let main_path = root.join("src").join("main.rs");
+    let lib_rs = register_module(&read_file(&lib_path)?, &module)?;
+    let main_rs = register_day(&read_file(&main_path)?, &module)?;
+
+    write_file(&module_path, &get_module_source(day))?;
+    write_file(&lib_path, &lib_rs)?;
+    write_file(&main_path, &main_rs)?;
+    let mut written = vec![module_path, lib_path, main_path];
+
+    let inputs_dir = root.join("inputs");
+    fs::create_dir_all(&inputs_dir)
+        .map_err(|err| format!("failed to create {}: {}", inputs_dir.display(), err))?;
+    for file_name in [format!("{}.txt", day), format!("{}_example.txt", day)].iter() {
+        let path = inputs_dir.join(file_name);
+        if !path.exists() {
+            write_file(&path, "")?;
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("failed to write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_names() {
+        assert_eq!(Ok("one".to_string()), get_module_name(1));
+        assert_eq!(Ok("thirteen".to_string()), get_module_name(13));
+        assert_eq!(Ok("twenty".to_string()), get_module_name(20));
+        assert_eq!(Ok("twenty_two".to_string()), get_module_name(22));
+        assert!(get_module_name(0).is_err());
+        assert!(get_module_name(26).is_err());
+    }
+
+    #[test]
+    fn register_module_sorted() {
+        let lib_rs = "pub mod twenty;\npub mod twenty_one;\npub mod two;\n";
+
+        assert_eq!(
+            "pub mod twenty;\npub mod twenty_one;\npub mod twenty_two;\npub mod two;\n",
+            register_module(lib_rs, "twenty_two").unwrap()
+        );
+        assert!(register_module(lib_rs, "twenty").is_err());
+    }
+
+    #[test]
+    fn register_day_appends() {
+        let main_rs = "fn get_days() -> Vec<AdventOfCodeDay> {\n    vec![\n        \
+                       day::<one::Solver>(),\n    ]\n}\n";
+
+        assert_eq!(
+            "fn get_days() -> Vec<AdventOfCodeDay> {\n    vec![\n        \
+             day::<one::Solver>(),\n        day::<two::Solver>(),\n    ]\n}\n",
+            register_day(main_rs, "two").unwrap()
+        );
+        assert!(register_day(main_rs, "one").is_err());
+    }
+
+    #[test]
+    fn template_has_day() {
+        let source = get_module_source(22);
+
+        assert!(source.contains("const DAY: u32 = 22;"));
+        assert!(source.contains("inputs/22_example.txt"));
+        assert!(!source.contains("{{"));
+    }
+}
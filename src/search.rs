@@ -0,0 +1,216 @@
+extern crate priority_queue;
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use num_traits::Zero;
+use priority_queue::PriorityQueue;
+
+/// Finds a path with the fewest steps from `start` to a node accepted by `is_goal`, returning
+/// every node along it including `start` and the goal.
+pub fn bfs<N, G, F, I>(start: N, mut is_goal: G, mut neighbors: F) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, Option<N>> = HashMap::new();
+    let mut queue: VecDeque<N> = VecDeque::new();
+
+    parents.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+
+        for n in neighbors(&node) {
+            if let Entry::Vacant(entry) = parents.entry(n.clone()) {
+                entry.insert(Some(node.clone()));
+                queue.push_back(n);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns every node reachable from `start`, in the order they were reached.
+pub fn flood_fill<N, F, I>(start: N, mut neighbors: F) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached: Vec<N> = vec![];
+    bfs(
+        start,
+        |node| {
+            reached.push(node.clone());
+            false
+        },
+        &mut neighbors,
+    );
+
+    reached
+}
+
+/// Finds a cheapest path from `start` to a node accepted by `is_goal`, where `neighbors` gives
+/// each neighboring node along with the cost of moving to it. Returns the path, including `start`
+/// and the goal, and its total cost.
+pub fn dijkstra<N, C, G, F, I>(start: N, is_goal: G, neighbors: F) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, is_goal, neighbors, |_| C::zero())
+}
+
+/// Like `dijkstra`, but guided by `heuristic`, which estimates the remaining cost from a node to
+/// the goal. The heuristic must never overestimate for the path found to be a cheapest one.
+pub fn astar<N, C, G, F, I, H>(
+    start: N,
+    mut is_goal: G,
+    mut neighbors: F,
+    mut heuristic: H,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+{
+    let mut queue: PriorityQueue<N, Reverse<C>> = PriorityQueue::new();
+    let mut costs: HashMap<N, C> = HashMap::new();
+    let mut parents: HashMap<N, Option<N>> = HashMap::new();
+
+    queue.push(start.clone(), Reverse(heuristic(&start)));
+    costs.insert(start.clone(), C::zero());
+    parents.insert(start, None);
+
+    while let Some((node, _)) = queue.pop() {
+        let cost = costs[&node];
+        if is_goal(&node) {
+            return Some((reconstruct_path(&parents, node), cost));
+        }
+
+        for (n, step_cost) in neighbors(&node) {
+            let new_cost = cost + step_cost;
+            if costs.get(&n).map(|c| new_cost < *c).unwrap_or(true) {
+                costs.insert(n.clone(), new_cost);
+                parents.insert(n.clone(), Some(node.clone()));
+
+                let priority = Reverse(new_cost + heuristic(&n));
+                queue.push(n, priority);
+            }
+        }
+    }
+
+    None
+}
+
+/// Counts the distinct paths from `start` to nodes accepted by `is_goal`, exploring depth first.
+/// Paths stop at the first goal they reach. There must be no cycles reachable from `start`, which
+/// usually means the nodes carry whatever state stops a path from revisiting places.
+pub fn count_paths<N, G, F, I>(start: N, mut is_goal: G, mut neighbors: F) -> usize
+where
+    G: FnMut(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    fn count<N, G, F, I>(node: &N, is_goal: &mut G, neighbors: &mut F) -> usize
+    where
+        G: FnMut(&N) -> bool,
+        F: FnMut(&N) -> I,
+        I: IntoIterator<Item = N>,
+    {
+        if is_goal(node) {
+            return 1;
+        }
+
+        let next: Vec<N> = neighbors(node).into_iter().collect();
+        next.iter().map(|n| count(n, is_goal, neighbors)).sum()
+    }
+
+    count(&start, &mut is_goal, &mut neighbors)
+}
+
+fn reconstruct_path<N>(parents: &HashMap<N, Option<N>>, end: N) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut path = vec![end];
+    while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A line of nodes 0..10 where each node can step forward by 1 or 3, with steps of 3 costing 5.
+    fn steps(n: &i32) -> Vec<(i32, i32)> {
+        vec![(n + 1, 1), (n + 3, 5)]
+            .into_iter()
+            .filter(|(m, _)| *m < 10)
+            .collect()
+    }
+
+    #[test]
+    fn bfs_fewest_steps() {
+        let path = bfs(0, |n| *n == 9, |n| steps(n).into_iter().map(|(m, _)| m));
+
+        assert_eq!(Some(vec![0, 3, 6, 9]), path);
+        assert_eq!(
+            None,
+            bfs(0, |n| *n == 10, |n| steps(n).into_iter().map(|(m, _)| m))
+        );
+    }
+
+    #[test]
+    fn flood_fill_reaches_all() {
+        let mut reached = flood_fill(5, |n| steps(n).into_iter().map(|(m, _)| m));
+        reached.sort_unstable();
+
+        assert_eq!(vec![5, 6, 7, 8, 9], reached);
+    }
+
+    #[test]
+    fn dijkstra_cheapest() {
+        let (path, cost) = dijkstra(0, |n| *n == 4, steps).unwrap();
+
+        assert_eq!(vec![0, 1, 2, 3, 4], path);
+        assert_eq!(4, cost);
+        assert_eq!(Some((vec![2], 0)), dijkstra(2, |n| *n == 2, steps));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let (path, cost) = astar(0, |n| *n == 9, steps, |n| 9 - n).unwrap();
+
+        assert_eq!(9, cost);
+        assert_eq!(10, path.len());
+    }
+
+    #[test]
+    fn count_paths_in_dag() {
+        assert_eq!(
+            3,
+            count_paths(0, |n| *n == 4, |n| steps(n).into_iter().map(|(m, _)| m))
+        );
+    }
+}
@@ -1,30 +1,41 @@
 use std::collections::HashMap;
-use std::io;
-use std::io::prelude::*;
 
 use num_bigint::BigInt;
 use num_traits::sign::Signed;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = calc_fuel_to_align_to_best_pos(&numbers);
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::parsing::parse_comma_list;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = calc_fuel_to_align_to_best_pos_2(&numbers);
+impl Solution for Solver {
+    const DAY: u32 = 7;
 
-    println!("{}", answer);
-}
+    type Input = Vec<BigInt>;
+    type PartOne = BigInt;
+    type PartTwo = BigInt;
+
+    fn parse(input: &str) -> Result<Vec<BigInt>, ParseError> {
+        read_input(input)
+    }
 
-fn read_input() -> Vec<BigInt> {
-    let stdin = io::stdin();
+    fn part_one(numbers: &Vec<BigInt>) -> BigInt {
+        calc_fuel_to_align_to_best_pos(numbers)
+    }
+
+    fn part_two(numbers: &Vec<BigInt>) -> BigInt {
+        calc_fuel_to_align_to_best_pos_2(numbers)
+    }
+}
 
-    let line: String = stdin.lock().lines().last().unwrap().unwrap();
+fn read_input(input: &str) -> Result<Vec<BigInt>, ParseError> {
+    let line = match SourceLine::all(input).last() {
+        Some(line) => line,
+        None => return Err(missing_line(1, "expected a list of numbers")),
+    };
 
-    return line.split(',').map(|n| n.parse().unwrap()).collect();
+    parse_comma_list(&line, line.text, "expected a crab position")
 }
 
 fn get_total_fuel_cost(p: BigInt, crabs: &[BigInt]) -> BigInt {
@@ -59,7 +70,7 @@ fn get_total_fuel_cost_2(p: BigInt, crabs: &[BigInt]) -> BigInt {
 
 fn calc_fuel_to_align_to_best_pos(crabs: &[BigInt]) -> BigInt {
     let mut best_pos: BigInt =
-        crabs.iter().map(|v| v.clone()).sum::<BigInt>() / BigInt::from(crabs.len()) - 1;
+        crabs.iter().cloned().sum::<BigInt>() / BigInt::from(crabs.len()) - 1;
 
     let mut min_fuel = BigInt::from(999999999);
     let mut done = false;
@@ -83,7 +94,7 @@ fn calc_fuel_to_align_to_best_pos(crabs: &[BigInt]) -> BigInt {
 
 fn calc_fuel_to_align_to_best_pos_2(crabs: &[BigInt]) -> BigInt {
     let mut best_pos: BigInt =
-        crabs.iter().map(|v| v.clone()).sum::<BigInt>() / BigInt::from(crabs.len()) - 1;
+        crabs.iter().cloned().sum::<BigInt>() / BigInt::from(crabs.len()) - 1;
 
     let mut min_fuel = BigInt::from(2).pow(100000);
     let mut done = false;
@@ -104,3 +115,22 @@ fn calc_fuel_to_align_to_best_pos_2(crabs: &[BigInt]) -> BigInt {
 
     min_fuel
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/7_example.txt")).unwrap();
+
+        assert_eq!(BigInt::from(37), Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/7_example.txt")).unwrap();
+
+        assert_eq!(BigInt::from(168), Solver::part_two(&input));
+    }
+}
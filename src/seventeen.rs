@@ -1,101 +1,110 @@
-use std::cmp::Ordering;
-use std::io;
-use std::io::prelude::*;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-pub fn part_one() {
-    let range = read_input();
-    let answer = solve_1(&range);
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::geometry::Point2;
+use crate::params::Params;
+use crate::parsing::match_pattern;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 17;
+
+    type Input = Range2D;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Range2D, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(range: &Range2D) -> i32 {
+        Self::part_one_with(range, &Params::default()).unwrap()
+    }
 
-pub fn part_two() {
-    let range = read_input();
-    let answer = solve_2(&range);
+    fn part_one_with(range: &Range2D, _params: &Params) -> Result<i32, String> {
+        solve_1(range).ok_or_else(|| "no velocity reaches the target area".to_string())
+    }
 
-    println!("{}", answer);
+    fn part_two(range: &Range2D) -> i32 {
+        solve_2(range)
+    }
 }
 
 #[derive(Debug)]
-struct Range2D {
-    x_min: i32,
-    x_max: i32,
-    y_min: i32,
-    y_max: i32,
+pub struct Range2D {
+    min: Point2,
+    max: Point2,
 }
 
 impl Range2D {
-    fn contains(&self, position: &(i32, i32)) -> bool {
-        position.0 >= self.y_min
-            && position.0 <= self.y_max
-            && position.1 >= self.x_min
-            && position.1 <= self.x_max
+    fn contains(&self, position: &Point2) -> bool {
+        position.x >= self.min.x
+            && position.x <= self.max.x
+            && position.y >= self.min.y
+            && position.y <= self.max.y
     }
 
-    fn is_beyond(&self, position: &(i32, i32)) -> bool {
-        position.1 >= self.x_max || position.0 < self.y_min
+    fn is_beyond(&self, position: &Point2) -> bool {
+        position.x >= self.max.x || position.y < self.min.y
     }
 
-    fn from_str(range_str: &str) -> Range2D {
-        let parts: Vec<&str> = range_str.split(", ").collect();
-
-        let parts_x = parts[0].split(' ').collect::<Vec<&str>>()[2];
-        let parts_y = parts[1];
-
-        let x_min: i32 = parts_x.split("..").collect::<Vec<&str>>()[0]
-            .split('=')
-            .collect::<Vec<&str>>()[1]
-            .parse()
-            .unwrap();
-        let x_max: i32 = parts_x.split("..").collect::<Vec<&str>>()[1]
-            .parse()
-            .unwrap();
-
-        let y_min: i32 = parts_y.split("..").collect::<Vec<&str>>()[0]
-            .split('=')
-            .collect::<Vec<&str>>()[1]
-            .parse()
-            .unwrap();
-        let y_max: i32 = parts_y.split("..").collect::<Vec<&str>>()[1]
-            .parse()
-            .unwrap();
-
-        Range2D {
-            x_min,
-            x_max,
-            y_min,
-            y_max,
+    fn from_str(line: &SourceLine) -> Result<Range2D, ParseError> {
+        let bounds = match_pattern(
+            line,
+            "target area: x={}..{}, y={}..{}",
+            "expected a target area of the form target area: x=A..B, y=C..D",
+        )?;
+
+        let x_min = line.parse(bounds[0], "expected a lower x bound")?;
+        let x_max = line.parse(bounds[1], "expected an upper x bound")?;
+        let y_min = line.parse(bounds[2], "expected a lower y bound")?;
+        let y_max = line.parse(bounds[3], "expected an upper y bound")?;
+        if x_min > x_max {
+            return Err(line.error(
+                bounds[1],
+                "expected an upper x bound no less than the lower",
+            ));
         }
+        if y_min > y_max {
+            return Err(line.error(
+                bounds[3],
+                "expected an upper y bound no less than the lower",
+            ));
+        }
+
+        Ok(Range2D {
+            min: Point2::new(x_min, y_min),
+            max: Point2::new(x_max, y_max),
+        })
     }
 }
 
-fn read_input() -> Range2D {
-    let stdin = io::stdin();
-
-    let line = stdin.lock().lines().last().unwrap().unwrap();
-
-    Range2D::from_str(&line)
+fn read_input(input: &str) -> Result<Range2D, ParseError> {
+    match SourceLine::all(input).last() {
+        Some(line) => Range2D::from_str(&line),
+        None => Err(missing_line(1, "expected a target area")),
+    }
 }
 
-fn lands_in_range(mut velocity_x: i32, mut velocity_y: i32, range: &Range2D) -> Option<(u32, i32)> {
+fn lands_in_range(velocity_x: i32, velocity_y: i32, range: &Range2D) -> Option<(u32, i32)> {
     let mut step = 0;
 
-    let mut position = (0, 0);
+    let mut velocity = Point2::new(velocity_x, velocity_y);
+    let mut position = Point2::ORIGIN;
     let mut highest_y = 0;
     loop {
         step += 1;
 
-        position = (position.0 + velocity_y, position.1 + velocity_x);
+        position += velocity;
 
-        velocity_x = match velocity_x.cmp(&0) {
-            Ordering::Greater => velocity_x - 1,
-            Ordering::Less => velocity_x + 1,
-            Ordering::Equal => 0,
-        };
-        velocity_y -= 1;
+        velocity.x -= velocity.x.signum();
+        velocity.y -= 1;
 
-        if position.0 > highest_y {
-            highest_y = position.0;
+        if position.y > highest_y {
+            highest_y = position.y;
         }
 
         if range.contains(&position) {
@@ -110,27 +119,57 @@ fn lands_in_range(mut velocity_x: i32, mut velocity_y: i32, range: &Range2D) ->
     None
 }
 
-fn solve_1(range: &Range2D) -> i32 {
+fn solve_1(range: &Range2D) -> Option<i32> {
     for vel_x in 0..400 {
         for vel_y in (0..400).rev() {
             if let Some((_, highest_y)) = lands_in_range(vel_x, vel_y, range) {
-                return highest_y;
+                return Some(highest_y);
             }
         }
     }
 
-    panic!()
+    None
 }
 
 fn solve_2(range: &Range2D) -> i32 {
-    let mut count = 0;
-    for vel_x in 0..400 {
-        for vel_y in (-400..400).rev() {
-            if lands_in_range(vel_x, vel_y, range).is_some() {
-                count += 1;
-            }
-        }
+    #[cfg(feature = "parallel")]
+    let velocities_x = (0..400).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let velocities_x = 0..400;
+
+    velocities_x
+        .map(|vel_x| {
+            (-400..400)
+                .filter(|vel_y| lands_in_range(vel_x, *vel_y, range).is_some())
+                .count() as i32
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/17_example.txt")).unwrap();
+
+        assert_eq!(45, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/17_example.txt")).unwrap();
+
+        assert_eq!(112, Solver::part_two(&input));
     }
 
-    count
+    #[test]
+    fn invalid_target() {
+        let err = Solver::parse("target area: x=5..1, y=-3..-1\n").unwrap_err();
+        assert_eq!((1, 19), (err.line, err.column));
+
+        let input = Solver::parse("target area: x=-20..-10, y=-3..-1\n").unwrap();
+        assert!(Solver::part_one_with(&input, &Params::default()).is_err());
+    }
 }
@@ -0,0 +1,368 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::submissions::Verdict;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2021";
+pub const SESSION_ENV_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_SESSION_FILE: &str = ".session";
+
+/// Where to find the puzzle site and the session token to log in to it with.
+#[derive(Clone, Debug)]
+pub struct SiteConfig {
+    pub base_url: String,
+    pub session: String,
+}
+
+impl SiteConfig {
+    /// Reads the session token from the `AOC_SESSION` environment variable, falling back to the
+    /// contents of `session_file`. The base URL is taken from `AOC_BASE_URL` when it is set.
+    pub fn load(session_file: &Path) -> Result<SiteConfig, String> {
+        let session = match env::var(SESSION_ENV_VAR) {
+            Ok(session) => session,
+            Err(_) => fs::read_to_string(session_file).map_err(|err| {
+                format!(
+                    "no session token: set {} or write it to {} ({})",
+                    SESSION_ENV_VAR,
+                    session_file.display(),
+                    err
+                )
+            })?,
+        };
+
+        let session = session.trim().to_string();
+        if session.is_empty() {
+            return Err("session token is empty".to_string());
+        }
+
+        let base_url = env::var(BASE_URL_ENV_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+        Ok(SiteConfig { base_url, session })
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> SiteConfig {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(method, &self.url(path))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+    }
+}
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum FetchOutcome {
+    Downloaded(PathBuf),
+    Cached(PathBuf),
+}
+
+/// Returns the path that a day's input is cached at.
+pub fn get_input_path(inputs_dir: &Path, day: u32) -> PathBuf {
+    inputs_dir.join(format!("{}.txt", day))
+}
+
+/// Downloads the input for a day into `inputs_dir`, unless it has already been downloaded. An empty
+/// file, such as the placeholder created by `new`, does not count as downloaded.
+pub fn fetch_input(
+    config: &SiteConfig,
+    day: u32,
+    inputs_dir: &Path,
+) -> Result<FetchOutcome, String> {
+    let path = get_input_path(inputs_dir, day);
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(FetchOutcome::Cached(path));
+    }
+
+    let response = config
+        .request("GET", &format!("/day/{}/input", day))
+        .call()
+        .map_err(|err| format!("failed to download input for day {}: {}", day, err))?;
+    let input = response
+        .into_string()
+        .map_err(|err| format!("failed to read input for day {}: {}", day, err))?;
+
+    fs::create_dir_all(inputs_dir)
+        .map_err(|err| format!("failed to create {}: {}", inputs_dir.display(), err))?;
+    fs::write(&path, input)
+        .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+
+    Ok(FetchOutcome::Downloaded(path))
+}
+
+/// Posts an answer to one part of a day, returning the site's verdict and how many seconds it
+/// asked us to wait before submitting again.
+pub fn submit_answer(
+    config: &SiteConfig,
+    day: u32,
+    part: &str,
+    answer: &str,
+) -> Result<(Verdict, u64), String> {
+    let level = match part {
+        "one" => "1",
+        "two" => "2",
+        p => return Err(format!("Unknown part: {}", p)),
+    };
+
+    let response = config
+        .request("POST", &format!("/day/{}/answer", day))
+        .send_form(&[("level", level), ("answer", answer)])
+        .map_err(|err| format!("failed to submit answer for day {}: {}", day, err))?;
+    let body = response
+        .into_string()
+        .map_err(|err| format!("failed to read response for day {}: {}", day, err))?;
+
+    read_verdict(&body)
+}
+
+fn read_verdict(body: &str) -> Result<(Verdict, u64), String> {
+    let verdict = if body.contains("That's the right answer") {
+        Verdict::Correct
+    } else if body.contains("answer is too high") {
+        Verdict::TooHigh
+    } else if body.contains("answer is too low") {
+        Verdict::TooLow
+    } else if body.contains("That's not the right answer") {
+        Verdict::Incorrect
+    } else if body.contains("You gave an answer too recently") {
+        Verdict::Wait
+    } else {
+        return Err(format!("unrecognized response: {}", body.trim()));
+    };
+
+    Ok((verdict, read_wait_secs(body)))
+}
+
+/// Finds how long the site asked us to wait, from either "You have 1m 30s left to wait" or
+/// "Please wait 5 minutes before trying again".
+fn read_wait_secs(body: &str) -> u64 {
+    if let Some(end) = body.find(" left to wait") {
+        let start = body[..end].rfind("You have ").map(|i| i + 9).unwrap_or(end);
+
+        return body[start..end]
+            .split_whitespace()
+            .map(|part| {
+                let (num, unit) = part.split_at(part.len() - 1);
+                let num: u64 = num.parse().unwrap_or(0);
+                match unit {
+                    "h" => num * 60 * 60,
+                    "m" => num * 60,
+                    _ => num,
+                }
+            })
+            .sum();
+    }
+
+    if let Some(start) = body.find("wait ") {
+        let mut words = body[start + 5..].split_whitespace();
+        let num = match words.next() {
+            Some("one") => 1,
+            Some(num) => num.parse().unwrap_or(0),
+            None => 0,
+        };
+
+        if let Some(unit) = words.next() {
+            if unit.starts_with("minute") {
+                return num * 60;
+            }
+            if unit.starts_with("second") {
+                return num;
+            }
+        }
+    }
+
+    0
+}
+
+#[cfg(test)]
+mod stub {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// A request received by the stub server.
+    #[derive(Debug)]
+    pub struct Request {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Starts a server on a local port that answers each request with the next of `responses`,
+    /// returning its base URL and a channel of the requests it received.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut parts = request_line.split_whitespace();
+                let method = parts.next().unwrap_or("").to_string();
+                let path = parts.next().unwrap_or("").to_string();
+
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.trim().to_string(), value.trim().to_string()));
+                    }
+                }
+
+                let length = headers
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                    .map(|(_, v)| v.parse().unwrap())
+                    .unwrap_or(0);
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+
+                let _ = sender.send(Request {
+                    method,
+                    path,
+                    headers,
+                    body: String::from_utf8(request_body).unwrap(),
+                });
+            }
+        });
+
+        (base_url, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc_2021_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn config(base_url: &str) -> SiteConfig {
+        SiteConfig {
+            base_url: base_url.to_string(),
+            session: "abc123".to_string(),
+        }
+    }
+
+    #[test]
+    fn fetch_downloads_then_uses_cache() {
+        let (base_url, requests) = stub::serve(vec![(200, "1\n2\n3\n")]);
+        let dir = temp_dir("fetch_cache");
+
+        let outcome = fetch_input(&config(&base_url), 1, &dir).unwrap();
+        assert_eq!(FetchOutcome::Downloaded(dir.join("1.txt")), outcome);
+        assert_eq!("1\n2\n3\n", fs::read_to_string(dir.join("1.txt")).unwrap());
+
+        let request = requests.recv().unwrap();
+        assert_eq!("GET", request.method);
+        assert_eq!("/day/1/input", request.path);
+        assert_eq!(Some("session=abc123"), request.header("Cookie"));
+
+        let outcome = fetch_input(&config(&base_url), 1, &dir).unwrap();
+        assert_eq!(FetchOutcome::Cached(dir.join("1.txt")), outcome);
+        assert!(requests.try_recv().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_replaces_empty_placeholder() {
+        let (base_url, _requests) = stub::serve(vec![(200, "4\n5\n")]);
+        let dir = temp_dir("fetch_placeholder");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("22.txt"), "").unwrap();
+
+        let outcome = fetch_input(&config(&base_url), 22, &dir).unwrap();
+        assert_eq!(FetchOutcome::Downloaded(dir.join("22.txt")), outcome);
+        assert_eq!("4\n5\n", fs::read_to_string(dir.join("22.txt")).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_error_does_not_cache() {
+        let (base_url, _requests) = stub::serve(vec![(400, "Please log in")]);
+        let dir = temp_dir("fetch_error");
+
+        assert!(fetch_input(&config(&base_url), 2, &dir).is_err());
+        assert!(!dir.join("2.txt").exists());
+    }
+
+    #[test]
+    fn submit_posts_answer() {
+        let (base_url, requests) = stub::serve(vec![(
+            200,
+            "<article><p>That's not the right answer; your answer is too high. \
+             Please wait one minute before trying again.</p></article>",
+        )]);
+
+        let verdict = submit_answer(&config(&base_url), 3, "two", "1234").unwrap();
+        assert_eq!((Verdict::TooHigh, 60), verdict);
+
+        let request = requests.recv().unwrap();
+        assert_eq!("POST", request.method);
+        assert_eq!("/day/3/answer", request.path);
+        assert_eq!("level=2&answer=1234", request.body);
+    }
+
+    #[test]
+    fn read_verdict_responses() {
+        assert_eq!(
+            Ok((Verdict::Correct, 0)),
+            read_verdict("That's the right answer! You are one gold star closer.")
+        );
+        assert_eq!(
+            Ok((Verdict::TooLow, 300)),
+            read_verdict("your answer is too low. Please wait 5 minutes before trying again.")
+        );
+        assert_eq!(
+            Ok((Verdict::Wait, 95)),
+            read_verdict("You gave an answer too recently. You have 1m 35s left to wait.")
+        );
+        assert!(read_verdict("<html>Puzzle inputs differ by user.</html>").is_err());
+    }
+}
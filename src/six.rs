@@ -1,27 +1,55 @@
 use std::collections::HashMap;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let mut numbers = read_input();
-    let answer = get_num_fish_after_n_days(&mut numbers, 80);
+use num_bigint::BigInt;
 
-    println!("{}", answer);
-}
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::params::Params;
+use crate::parsing::parse_comma_list;
+use crate::solution::Solution;
 
-pub fn part_two() {
-    let mut numbers = read_input();
-    let answer = get_num_fish_after_n_days_calc(&mut numbers, 256);
+pub struct Solver;
 
-    println!("{}", answer);
-}
+impl Solution for Solver {
+    const DAY: u32 = 6;
+    const PARAMS: &'static [&'static str] = &["days"];
+
+    type Input = Vec<i64>;
+    type PartOne = i64;
+    type PartTwo = BigInt;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(numbers: &Vec<i64>) -> i64 {
+        Self::part_one_with(numbers, &Params::default()).unwrap()
+    }
+
+    fn part_two(numbers: &Vec<i64>) -> BigInt {
+        Self::part_two_with(numbers, &Params::default()).unwrap()
+    }
+
+    fn part_one_with(numbers: &Vec<i64>, params: &Params) -> Result<i64, String> {
+        let num_days: u32 = params.get("days", 80)?;
+        let mut numbers = numbers.clone();
+
+        Ok(get_num_fish_after_n_days(&mut numbers, num_days as i64))
+    }
+
+    fn part_two_with(numbers: &Vec<i64>, params: &Params) -> Result<BigInt, String> {
+        let num_days: u32 = params.get("days", 256)?;
 
-fn read_input() -> Vec<i64> {
-    let stdin = io::stdin();
+        Ok(get_num_fish_after_n_days_calc(numbers, num_days as i64))
+    }
+}
 
-    let line: String = stdin.lock().lines().last().unwrap().unwrap();
+fn read_input(input: &str) -> Result<Vec<i64>, ParseError> {
+    let line = match SourceLine::all(input).last() {
+        Some(line) => line,
+        None => return Err(missing_line(1, "expected a list of numbers")),
+    };
 
-    return line.split(',').map(|n| n.parse().unwrap()).collect();
+    parse_comma_list(&line, line.text, "expected a timer value")
 }
 
 fn simulate_day(fish: &mut Vec<i64>) {
@@ -47,10 +75,10 @@ fn get_num_fish_after_n_days(fish: &mut Vec<i64>, num_days: i64) -> i64 {
     fish.len() as i64
 }
 
-fn get_num_fish_after_n_days_calc(fish: &mut Vec<i64>, num_days: i64) -> i64 {
-    let mut cache: HashMap<(i64, i64), i64> = HashMap::new();
+fn get_num_fish_after_n_days_calc(fish: &[i64], num_days: i64) -> BigInt {
+    let mut cache: HashMap<(i64, i64), BigInt> = HashMap::new();
 
-    let mut total = 0;
+    let mut total = BigInt::from(0);
     for f in fish.iter() {
         total += calc(&mut cache, num_days, *f);
     }
@@ -58,9 +86,9 @@ fn get_num_fish_after_n_days_calc(fish: &mut Vec<i64>, num_days: i64) -> i64 {
     total
 }
 
-fn calc(cache: &mut HashMap<(i64, i64), i64>, mut num_days: i64, mut f: i64) -> i64 {
+fn calc(cache: &mut HashMap<(i64, i64), BigInt>, mut num_days: i64, mut f: i64) -> BigInt {
     if num_days == 0 {
-        return 1;
+        return BigInt::from(1);
     }
 
     if f > 0 && num_days >= f {
@@ -71,18 +99,47 @@ fn calc(cache: &mut HashMap<(i64, i64), i64>, mut num_days: i64, mut f: i64) ->
         num_days = 0;
     }
 
-    if cache.contains_key(&(num_days, f)) {
-        return cache[&(num_days, f)];
+    if let Some(value) = cache.get(&(num_days, f)) {
+        return value.clone();
     }
 
     if num_days > 0 {
         num_days -= 1;
         let value = calc(cache, num_days, 6) + calc(cache, num_days, 8);
 
-        cache.insert((num_days + 1, f), value);
+        cache.insert((num_days + 1, f), value.clone());
 
         value
     } else {
-        1
+        BigInt::from(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::read_params;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/6_example.txt")).unwrap();
+
+        assert_eq!(5934, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/6_example.txt")).unwrap();
+
+        assert_eq!(BigInt::from(26984457539u64), Solver::part_two(&input));
+    }
+
+    #[test]
+    fn days_param() {
+        let input = Solver::parse(include_str!("../inputs/6_example.txt")).unwrap();
+        let params = read_params(vec!["days=18"]).unwrap();
+
+        assert_eq!(Ok(26), Solver::part_one_with(&input, &params));
+        assert_eq!(Ok(BigInt::from(26)), Solver::part_two_with(&input, &params));
     }
 }
@@ -1,18 +1,26 @@
-use std::io;
-use std::io::prelude::*;
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::solution::Solution;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = solve_1(&numbers);
+pub struct Solver;
 
-    println!("{}", answer);
-}
+impl Solution for Solver {
+    const DAY: u32 = 16;
+
+    type Input = Packet;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = solve_2(&numbers);
+    fn parse(input: &str) -> Result<Packet, ParseError> {
+        read_input(input)
+    }
 
-    println!("{}", answer);
+    fn part_one(packet: &Packet) -> i64 {
+        packet.get_sum_versions()
+    }
+
+    fn part_two(packet: &Packet) -> i64 {
+        packet.calc()
+    }
 }
 
 fn i64_to_bits(value: i64) -> Vec<u8> {
@@ -41,36 +49,76 @@ fn bits_to_i64(bits: &[u8]) -> i64 {
     value
 }
 
-fn read_input() -> Vec<u8> {
-    let stdin = io::stdin();
+fn read_input(input: &str) -> Result<Packet, ParseError> {
+    let line = match SourceLine::all(input).last() {
+        Some(line) if !line.text.is_empty() => line,
+        _ => return Err(missing_line(1, "expected a hexadecimal transmission")),
+    };
+
+    let mut hex_nums: Vec<i64> = vec![];
+    for (i, h) in line.text.char_indices() {
+        match h.to_digit(16) {
+            Some(value) => hex_nums.push(value as i64),
+            None => return Err(line.error_char(i, "expected a hexadecimal digit")),
+        }
+    }
 
-    let line = stdin.lock().lines().last().unwrap().unwrap();
+    let mut bits = Bits {
+        line: &line,
+        bits: hex_nums.iter().flat_map(|d| i64_to_bits(*d)).collect(),
+        position: 0,
+    };
 
-    let hex_nums: Vec<i64> = line
-        .chars()
-        .map(|h| i64::from_str_radix(&(h.to_string()), 16).unwrap())
-        .collect();
+    Packet::from_bits(&mut bits)
+}
 
-    hex_nums.iter().map(|d| i64_to_bits(*d)).flatten().collect()
+/// The bits of a transmission, read from the front, along with the line they were decoded from so
+/// that errors can point at the hex digit where decoding went wrong.
+struct Bits<'a> {
+    line: &'a SourceLine<'a>,
+    bits: Vec<u8>,
+    position: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn read(&mut self, count: usize) -> Result<i64, ParseError> {
+        if self.position + count > self.bits.len() {
+            return Err(self.line.error_char(
+                self.line.text.len(),
+                "transmission ended in the middle of a packet",
+            ));
+        }
+
+        let value = bits_to_i64(&self.bits[self.position..(self.position + count)]);
+        self.position += count;
+
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let index = (self.position / 4).min(self.line.text.len());
+
+        self.line.error_char(index, message)
+    }
 }
 
 #[derive(Debug)]
-struct Header {
+pub struct Header {
     version: i64,
     packet_id: i64,
 }
 
 impl Header {
-    fn from_bits(buffer: &[u8], start: usize) -> Header {
-        let version = bits_to_i64(&buffer[start..(start + 3)]);
-        let packet_id = bits_to_i64(&buffer[(start + 3)..(start + 6)]);
+    fn from_bits(bits: &mut Bits) -> Result<Header, ParseError> {
+        let version = bits.read(3)?;
+        let packet_id = bits.read(3)?;
 
-        Header { version, packet_id }
+        Ok(Header { version, packet_id })
     }
 }
 
 #[derive(Debug)]
-enum Packet {
+pub enum Packet {
     LiteralValue(Header, i64),
     Operator(Header, Vec<Packet>),
 }
@@ -107,89 +155,136 @@ impl Packet {
         }
     }
 
-    fn from_bits(buffer: &[u8], start: usize) -> (Packet, usize) {
-        let header = Header::from_bits(buffer, start);
+    fn from_bits(bits: &mut Bits) -> Result<Packet, ParseError> {
+        let header = Header::from_bits(bits)?;
 
         match header.packet_id {
-            4 => Packet::literal_value_from_bits(header, buffer, start + 6),
-            _ => Packet::operator_from_bits(header, buffer, start + 6),
+            4 => Packet::literal_value_from_bits(header, bits),
+            _ => Packet::operator_from_bits(header, bits),
         }
     }
 
-    fn literal_value_from_bits(header: Header, buffer: &[u8], start: usize) -> (Packet, usize) {
+    fn literal_value_from_bits(header: Header, bits: &mut Bits) -> Result<Packet, ParseError> {
         let mut value = 0;
 
-        let mut i = start;
+        let mut num_groups = 0;
         let mut done = false;
         while !done {
-            if buffer[i] == 0 {
+            if bits.read(1)? == 0 {
                 done = true;
             }
 
-            i += 1;
+            num_groups += 1;
+            if num_groups > 15 {
+                return Err(bits.error("expected a literal value that fits in 64 bits"));
+            }
 
             value <<= 4;
-            value += bits_to_i64(&buffer[i..(i + 4)]);
-
-            i += 4;
+            value += bits.read(4)?;
         }
 
-        (Packet::LiteralValue(header, value), i)
+        Ok(Packet::LiteralValue(header, value))
     }
 
-    fn operator_from_bits(header: Header, buffer: &[u8], start: usize) -> (Packet, usize) {
-        let mut i = start;
-
-        let length_type_id = buffer[i];
-        i += 1;
-
+    fn operator_from_bits(header: Header, bits: &mut Bits) -> Result<Packet, ParseError> {
+        let length_type_id = bits.read(1)?;
         let length = match length_type_id {
-            0 => {
-                let len = bits_to_i64(&buffer[i..(i + 15)]);
-                i += 15;
-
-                len
-            }
-            1 => {
-                let len = bits_to_i64(&buffer[i..(i + 11)]);
-                i += 11;
-
-                len
-            }
-            _ => panic!(),
+            0 => bits.read(15)?,
+            _ => bits.read(11)?,
         };
 
-        let mut j = i;
-        let mut sub_i = 0;
+        let start = bits.position;
         let mut sub_packets = vec![];
         loop {
-            sub_i += 1;
-
-            if length_type_id == 0 && j >= i + length as usize {
+            if length_type_id == 0 && bits.position >= start + length as usize {
                 break;
             }
-            if length_type_id == 1 && sub_i > length {
+            if length_type_id == 1 && sub_packets.len() as i64 >= length {
                 break;
             }
 
-            let results = Packet::from_bits(buffer, j);
+            sub_packets.push(Packet::from_bits(bits)?);
+        }
 
-            j = results.1;
-            sub_packets.push(results.0);
+        let expected = match header.packet_id {
+            2 | 3 if sub_packets.is_empty() => Some("expected at least one sub-packet"),
+            5..=7 if sub_packets.len() != 2 => Some("expected exactly two sub-packets"),
+            _ => None,
+        };
+        if let Some(message) = expected {
+            return Err(bits.error(message));
         }
 
-        (Packet::Operator(header, sub_packets), j)
+        Ok(Packet::Operator(header, sub_packets))
     }
 }
 
-fn solve_1(data: &[u8]) -> i64 {
-    let packet = Packet::from_bits(data, 0);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    packet.0.get_sum_versions()
-}
+    #[test]
+    fn part_one_example_1() {
+        let input = Solver::parse(include_str!("../inputs/16_example_1.txt")).unwrap();
+
+        assert_eq!(6, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_1() {
+        let input = Solver::parse(include_str!("../inputs/16_example_1.txt")).unwrap();
+
+        assert_eq!(2021, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn part_one_example_2() {
+        let input = Solver::parse(include_str!("../inputs/16_example_2.txt")).unwrap();
+
+        assert_eq!(9, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_2() {
+        let input = Solver::parse(include_str!("../inputs/16_example_2.txt")).unwrap();
+
+        assert_eq!(1, Solver::part_two(&input));
+    }
 
-fn solve_2(data: &[u8]) -> i64 {
-    let packet = Packet::from_bits(data, 0);
+    #[test]
+    fn part_one_example_3() {
+        let input = Solver::parse(include_str!("../inputs/16_example_3.txt")).unwrap();
 
-    packet.0.calc()
+        assert_eq!(16, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_3() {
+        let input = Solver::parse(include_str!("../inputs/16_example_3.txt")).unwrap();
+
+        assert_eq!(15, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn part_one_example_4() {
+        let input = Solver::parse(include_str!("../inputs/16_example_4.txt")).unwrap();
+
+        assert_eq!(14, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_4() {
+        let input = Solver::parse(include_str!("../inputs/16_example_4.txt")).unwrap();
+
+        assert_eq!(3, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn truncated_transmission() {
+        let err = Solver::parse("1\n").unwrap_err();
+        assert_eq!((1, 2), (err.line, err.column));
+
+        let err = Solver::parse("8A00\n").unwrap_err();
+        assert_eq!("transmission ended in the middle of a packet", err.message);
+    }
 }
@@ -0,0 +1,158 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::params::Params;
+use crate::visualize::Frames;
+
+/// A solution to a single day's puzzle.
+///
+/// The puzzle input is parsed once by `parse` and then shared between the two parts, which return
+/// their answers rather than printing them. Each part's answer is converted into an `Answer`.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parses the input with parameters that change how it is read. Days that take such
+    /// parameters implement this and have `parse` call it with the defaults. Errors for lines that
+    /// the parameters allow to be skipped are pushed onto `warnings` rather than returned.
+    fn parse_with(
+        input: &str,
+        _params: &Params,
+        _warnings: &mut Vec<ParseError>,
+    ) -> Result<Self::Input, RunError> {
+        Self::parse(input).map_err(RunError::Parse)
+    }
+
+    fn part_one(input: &Self::Input) -> Self::PartOne;
+
+    fn part_two(input: &Self::Input) -> Self::PartTwo;
+
+    /// Names of the parameters read by `part_one_with` and `part_two_with`.
+    const PARAMS: &'static [&'static str] = &[];
+
+    /// Runs part one with parameters in place of its usual constants. Days that take parameters
+    /// implement this and have `part_one` call it with the defaults.
+    fn part_one_with(input: &Self::Input, _params: &Params) -> Result<Self::PartOne, String> {
+        Ok(Self::part_one(input))
+    }
+
+    /// Runs part two with parameters in place of its usual constants. See `part_one_with`.
+    fn part_two_with(input: &Self::Input, _params: &Params) -> Result<Self::PartTwo, String> {
+        Ok(Self::part_two(input))
+    }
+
+    /// Writes a frame for each step taken while solving the given part. Only days with a state
+    /// worth drawing implement this.
+    fn visualize(
+        _input: &Self::Input,
+        _part: &str,
+        _params: &Params,
+        _frames: &mut Frames,
+    ) -> Result<(), String> {
+        Err(format!("Day {} has no visualization", Self::DAY))
+    }
+}
+
+/// Parses a day's input and runs one of its parts.
+pub type PartRunner = fn(&str, &Params) -> Result<PartResult, RunError>;
+
+/// Parses a day's input and writes frames showing how one of its parts is solved.
+pub type Visualizer = fn(&str, &str, &Params, &mut Frames) -> Result<(), String>;
+
+/// Why a day's part could not be run.
+#[derive(Debug)]
+pub enum RunError {
+    Parse(ParseError),
+    Params(String),
+}
+
+impl RunError {
+    /// Records which day a parse error came from.
+    pub fn with_day(self, day: u32) -> RunError {
+        match self {
+            RunError::Parse(err) => RunError::Parse(err.with_day(day)),
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(err) => write!(f, "failed to parse input: {}", err),
+            RunError::Params(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// The answer to one part of a day, along with how long parsing and solving took and the errors
+/// for any input lines that were skipped.
+pub struct PartResult {
+    pub answer: Answer,
+    pub warnings: Vec<ParseError>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl PartResult {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+}
+
+pub fn run_part_one<S: Solution>(input: &str, params: &Params) -> Result<PartResult, RunError> {
+    run_part::<S, _>(input, params, S::part_one_with)
+}
+
+pub fn run_part_two<S: Solution>(input: &str, params: &Params) -> Result<PartResult, RunError> {
+    run_part::<S, _>(input, params, S::part_two_with)
+}
+
+fn run_part<S: Solution, A: Into<Answer>>(
+    input: &str,
+    params: &Params,
+    solve: fn(&S::Input, &Params) -> Result<A, String>,
+) -> Result<PartResult, RunError> {
+    params.check_names(S::PARAMS).map_err(RunError::Params)?;
+
+    let mut warnings = Vec::new();
+    let start = Instant::now();
+    let input = S::parse_with(input, params, &mut warnings).map_err(|err| err.with_day(S::DAY))?;
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let answer = solve(&input, params).map_err(RunError::Params)?.into();
+    let solve_time = start.elapsed();
+
+    Ok(PartResult {
+        answer,
+        warnings: warnings
+            .into_iter()
+            .map(|err| err.with_day(S::DAY))
+            .collect(),
+        parse_time,
+        solve_time,
+    })
+}
+
+pub fn run_visualize<S: Solution>(
+    input: &str,
+    part: &str,
+    params: &Params,
+    frames: &mut Frames,
+) -> Result<(), String> {
+    params.check_names(S::PARAMS)?;
+
+    // Skipped lines are only reported by the part's run, which always comes before this.
+    let input = S::parse_with(input, params, &mut Vec::new())
+        .map_err(|err| err.with_day(S::DAY).to_string())?;
+
+    S::visualize(&input, part, params, frames)
+}
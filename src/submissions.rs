@@ -0,0 +1,208 @@
+use std::fmt;
+
+/// How the puzzle site responded to a submitted answer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    Wait,
+}
+
+impl Verdict {
+    fn from_str(s: &str) -> Option<Verdict> {
+        match s {
+            "correct" => Some(Verdict::Correct),
+            "too_high" => Some(Verdict::TooHigh),
+            "too_low" => Some(Verdict::TooLow),
+            "incorrect" => Some(Verdict::Incorrect),
+            "wait" => Some(Verdict::Wait),
+            _ => None,
+        }
+    }
+
+    fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too_high",
+            Verdict::TooLow => "too_low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::Wait => "wait",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// An answer that was submitted, along with the site's response and how many seconds it asked us
+/// to wait before submitting again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Submission {
+    pub time: u64,
+    pub day: u32,
+    pub part: String,
+    pub verdict: Verdict,
+    pub wait_secs: u64,
+    pub answer: String,
+}
+
+impl Submission {
+    fn from_str(line: &str) -> Option<Submission> {
+        let parts: Vec<&str> = line.splitn(6, ' ').collect();
+        if parts.len() != 6 {
+            return None;
+        }
+
+        Some(Submission {
+            time: parts[0].parse().ok()?,
+            day: parts[1].parse().ok()?,
+            part: parts[2].to_string(),
+            verdict: Verdict::from_str(parts[3])?,
+            wait_secs: parts[4].parse().ok()?,
+            answer: parts[5].to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.time, self.day, self.part, self.verdict, self.wait_secs, self.answer
+        )
+    }
+}
+
+/// Every answer submitted so far, one `<time> <day> <part> <verdict> <wait> <answer>` entry per
+/// line.
+#[derive(Debug, Default)]
+pub struct History {
+    pub submissions: Vec<Submission>,
+}
+
+impl History {
+    /// Checks that submitting `answer` at time `now` is neither known to be wrong nor too soon
+    /// after the previous submission.
+    pub fn check(&self, day: u32, part: &str, answer: &str, now: u64) -> Result<(), String> {
+        if let Some(last) = self.submissions.last() {
+            let ready_at = last.time + last.wait_secs;
+            if now < ready_at {
+                return Err(format!(
+                    "must wait {}s before submitting again",
+                    ready_at - now
+                ));
+            }
+        }
+
+        let value: Option<i64> = answer.parse().ok();
+        for s in self
+            .submissions
+            .iter()
+            .filter(|s| s.day == day && s.part == part)
+        {
+            if s.verdict == Verdict::Correct {
+                return Err(format!("already solved with answer {}", s.answer));
+            }
+
+            if s.verdict.is_wrong() && s.answer == answer {
+                return Err(format!("{} was already submitted and was wrong", answer));
+            }
+
+            let previous: Option<i64> = s.answer.parse().ok();
+            if let (Some(value), Some(previous)) = (value, previous) {
+                if s.verdict == Verdict::TooHigh && value >= previous {
+                    return Err(format!("{} was too high, so {} is too", previous, value));
+                }
+                if s.verdict == Verdict::TooLow && value <= previous {
+                    return Err(format!("{} was too low, so {} is too", previous, value));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the history of submitted answers, skipping blank lines.
+pub fn read_history(contents: &str) -> Result<History, String> {
+    let mut submissions = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        match Submission::from_str(line) {
+            Some(submission) => submissions.push(submission),
+            None => return Err(format!("line {}: malformed submission: {}", i + 1, line)),
+        }
+    }
+
+    Ok(History { submissions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> History {
+        read_history(
+            "100 1 one too_high 60 500\n\
+             200 1 one too_low 60 100\n\
+             300 1 one incorrect 300 250\n\
+             700 2 one correct 0 42\n\
+             710 3 one wait 20 7\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn check_refuses_known_wrong_answers() {
+        let history = history();
+
+        assert!(history.check(1, "one", "250", 1000).is_err());
+        assert!(history.check(1, "one", "500", 1000).is_err());
+        assert!(history.check(1, "one", "600", 1000).is_err());
+        assert!(history.check(1, "one", "99", 1000).is_err());
+        assert!(history.check(1, "one", "300", 1000).is_ok());
+        assert!(history.check(1, "two", "500", 1000).is_ok());
+    }
+
+    #[test]
+    fn check_refuses_solved_parts() {
+        assert!(history().check(2, "one", "43", 1000).is_err());
+    }
+
+    #[test]
+    fn check_refuses_during_cooldown() {
+        let history = history();
+
+        assert!(history.check(4, "one", "1", 729).is_err());
+        assert!(history.check(4, "one", "1", 730).is_ok());
+    }
+
+    #[test]
+    fn round_trip() {
+        let history = history();
+        let contents: String = history
+            .submissions
+            .iter()
+            .map(|s| format!("{}\n", s))
+            .collect();
+
+        assert_eq!(
+            history.submissions,
+            read_history(&contents).unwrap().submissions
+        );
+    }
+}
@@ -1,32 +1,50 @@
 use std::collections::BTreeSet;
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let lines = read_input();
-    let answer = calc_syntax_error_score(&lines);
+use crate::error::{ParseError, SourceLine};
+use crate::params::Params;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = calc_autocomplete_score(&numbers);
+impl Solution for Solver {
+    const DAY: u32 = 10;
 
-    println!("{}", answer);
-}
+    type Input = Vec<String>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(lines: &Vec<String>) -> i64 {
+        calc_syntax_error_score(lines)
+    }
 
-fn read_input() -> Vec<String> {
-    let stdin = io::stdin();
+    fn part_two(lines: &Vec<String>) -> i64 {
+        Self::part_two_with(lines, &Params::default()).unwrap()
+    }
+
+    fn part_two_with(lines: &Vec<String>, _params: &Params) -> Result<i64, String> {
+        calc_autocomplete_score(lines).ok_or_else(|| "every line is corrupted".to_string())
+    }
+}
 
+fn read_input(input: &str) -> Result<Vec<String>, ParseError> {
     let mut lines: Vec<String> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
+    for line in SourceLine::all(input) {
+        if let Some((i, _)) = line
+            .text
+            .char_indices()
+            .find(|(_, c)| !PAIRS.iter().any(|(o, cl)| c == o || c == cl))
+        {
+            return Err(line.error_char(i, "expected a bracket of ()[]{}<>"));
+        }
 
-        lines.push(line);
+        lines.push(line.text.to_string());
     }
 
-    lines
+    Ok(lines)
 }
 
 const PAIRS: [(char, char); 4] = [('{', '}'), ('(', ')'), ('[', ']'), ('<', '>')];
@@ -61,15 +79,10 @@ fn score_errors(line: &str) -> i64 {
         if openings.contains(&c) {
             stack.push(c);
         } else if closings.contains(&c) {
-            match stack.pop() {
-                Some(o) => {
-                    if o == get_corresponding_opening(c) {
-                        // good
-                    } else {
-                        return get_bad_char_score(c);
-                    }
+            if let Some(o) = stack.pop() {
+                if o != get_corresponding_opening(c) {
+                    return get_bad_char_score(c);
                 }
-                None => {}
             }
         }
     }
@@ -88,15 +101,11 @@ fn score_autocomplete(line: &str) -> Option<i64> {
             stack.push(c);
         } else if closings.contains(&c) {
             match stack.pop() {
-                Some(o) => {
-                    if o == get_corresponding_opening(c) {
-                        // good
-                    } else {
-                        // ignore
-                        return None;
-                    }
+                Some(o) if o == get_corresponding_opening(c) => {
+                    // good
                 }
-                None => {
+                _ => {
+                    // ignore
                     return None;
                 }
             }
@@ -129,16 +138,41 @@ fn calc_syntax_error_score(lines: &[String]) -> i64 {
     total_score
 }
 
-fn calc_autocomplete_score(lines: &[String]) -> i64 {
+fn calc_autocomplete_score(lines: &[String]) -> Option<i64> {
     let mut scores: BTreeSet<i64> = BTreeSet::new();
     for line in lines.iter() {
-        match score_autocomplete(line) {
-            Some(s) => {
-                scores.insert(s);
-            }
-            None => {}
+        if let Some(s) = score_autocomplete(line) {
+            scores.insert(s);
         }
     }
 
-    scores.iter().cloned().collect::<Vec<i64>>()[scores.len() / 2]
+    scores.iter().nth(scores.len() / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/10_example.txt")).unwrap();
+
+        assert_eq!(26397, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/10_example.txt")).unwrap();
+
+        assert_eq!(288957, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let err = Solver::parse("()\n(a)\n").unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+
+        let input = Solver::parse("(]\n").unwrap();
+        assert!(Solver::part_two_with(&input, &Params::default()).is_err());
+    }
 }
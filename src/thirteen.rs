@@ -1,194 +1,223 @@
-use std::io;
-use std::io::prelude::*;
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::geometry::Point2;
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::parsing::{match_pattern, sections};
+use crate::solution::Solution;
+use crate::visualize::{Frames, Rgb, BLACK, WHITE};
 
-pub fn part_one() {
-    let (positions, folds) = read_input();
-    let answer = get_num_dots_after_first_fold(&positions, &folds);
+pub struct Solver;
 
-    println!("{}", answer);
+impl Solution for Solver {
+    const DAY: u32 = 13;
+
+    type Input = (Vec<Point2>, Vec<Fold>);
+    type PartOne = i32;
+    type PartTwo = Grid<bool>;
+
+    fn parse(input: &str) -> Result<(Vec<Point2>, Vec<Fold>), ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(input: &(Vec<Point2>, Vec<Fold>)) -> i32 {
+        let (positions, folds) = input;
+
+        get_num_dots_after_first_fold(positions, folds)
+    }
+
+    fn part_two(input: &(Vec<Point2>, Vec<Fold>)) -> Grid<bool> {
+        let (positions, folds) = input;
+
+        get_dots_after_folds(positions, folds).grid
+    }
+
+    fn visualize(
+        input: &(Vec<Point2>, Vec<Fold>),
+        part: &str,
+        _params: &Params,
+        frames: &mut Frames,
+    ) -> Result<(), String> {
+        let (positions, folds) = input;
+        let num_folds = match part {
+            "one" => 1,
+            _ => folds.len(),
+        };
+
+        let mut paper = Paper::from_dots(positions);
+        frames.write(&paper.grid, dot_color)?;
+        for fold in folds.iter().take(num_folds) {
+            paper = paper.fold(fold);
+            frames.write(&paper.grid, dot_color)?;
+        }
+
+        Ok(())
+    }
 }
 
-pub fn part_two() {
-    let (positions, folds) = read_input();
-    print_dots_after_folds(&&positions, &folds);
+fn dot_color(dot: &bool) -> Rgb {
+    if *dot {
+        WHITE
+    } else {
+        BLACK
+    }
 }
 
 struct Paper {
-    grid: Vec<Vec<bool>>,
+    grid: Grid<bool>,
 }
 
 impl Paper {
     fn fold(&self, fold: &Fold) -> Paper {
-        match fold {
-            Fold::X(col) => {
-                let mut new_grid = vec![vec![false; *col as usize]; self.grid.len()];
-
-                for (y, row) in self.grid.iter().enumerate() {
-                    for (x, v) in row.iter().enumerate() {
-                        if x < *col as usize {
-                            new_grid[y][x] = *v;
-                        }
-                    }
-                }
-
-                for c in *col as usize + 1..self.grid[0].len() {
-                    for y in 0..self.grid.len() {
-                        let src_x = c;
-                        let dest_x = (col - (c as i32 - col)) as usize;
-
-                        if dest_x > 100000000 {
-                            continue;
-                        }
-
-                        new_grid[y][dest_x] |= self.grid[y][src_x];
-                    }
-                }
-
-                Paper { grid: new_grid }
+        let (width, height) = match fold {
+            Fold::X(col) => (*col as usize, self.grid.height()),
+            Fold::Y(row) => (self.grid.width(), *row as usize),
+        };
+        let mut grid = Grid::new(width, height, false);
+
+        for ((x, y), dot) in self.grid.iter() {
+            if !dot {
+                continue;
             }
-            Fold::Y(row) => {
-                let mut new_grid = vec![vec![false; self.grid[0].len()]; *row as usize];
-
-                for (y, row_vec) in self.grid.iter().enumerate() {
-                    for (x, v) in row_vec.iter().enumerate() {
-                        if y < *row as usize {
-                            new_grid[y][x] = *v;
-                        }
-                    }
-                }
-
-                for x in 0..self.grid[0].len() {
-                    for y in *row as usize + 1..self.grid.len() {
-                        let src_y = y;
-                        let dest_y = (row - (y as i32 - row)) as usize;
-
-                        if dest_y > 100000000 {
-                            continue;
-                        }
-
-                        new_grid[dest_y][x] |= self.grid[src_y][x];
-                    }
-                }
-
-                Paper { grid: new_grid }
+
+            let (x, y) = match fold {
+                Fold::X(col) => (fold_coordinate(x, *col as usize), Some(y)),
+                Fold::Y(row) => (Some(x), fold_coordinate(y, *row as usize)),
+            };
+
+            if let (Some(x), Some(y)) = (x, y) {
+                grid.set(x, y, true);
             }
         }
+
+        Paper { grid }
     }
 
     fn get_num_dots(&self) -> i32 {
-        let mut total = 0;
-        for row in self.grid.iter() {
-            for v in row.iter() {
-                if *v {
-                    total += 1;
-                }
-            }
-        }
-
-        total
+        self.grid.values().filter(|dot| **dot).count() as i32
     }
 
-    fn from_dots(dots: &[Position]) -> Paper {
+    fn from_dots(dots: &[Point2]) -> Paper {
         let max_x = dots.iter().map(|p| p.x).max().unwrap() as usize;
         let max_y = dots.iter().map(|p| p.y).max().unwrap() as usize;
 
-        let mut grid = vec![vec![false; max_x + 1]; max_y + 1];
+        let mut grid = Grid::new(max_x + 1, max_y + 1, false);
 
         for pos in dots.iter() {
-            grid[pos.y as usize][pos.x as usize] = true;
+            grid.set(pos.x as usize, pos.y as usize, true);
         }
 
         Paper { grid }
     }
-
-    fn print(&self) {
-        for row in self.grid.iter() {
-            for v in row.iter() {
-                match v {
-                    true => print!("#"),
-                    false => print!("."),
-                }
-            }
-            println!();
-        }
-    }
 }
 
-#[derive(Debug)]
-struct Position {
-    x: i32,
-    y: i32,
+/// Returns where a coordinate ends up after folding along `line`, or `None` if it was on the fold.
+fn fold_coordinate(value: usize, line: usize) -> Option<usize> {
+    if value < line {
+        Some(value)
+    } else {
+        (2 * line).checked_sub(value).filter(|_| value != line)
+    }
 }
 
-impl Position {
-    fn from_str(position_str: &str) -> Position {
-        let parts: Vec<&str> = position_str.split(',').collect();
-
-        let x: i32 = parts[0].parse().unwrap();
-        let y: i32 = parts[1].parse().unwrap();
-
-        Position { x, y }
+fn read_dot(line: &SourceLine) -> Result<Point2, ParseError> {
+    let dot = Point2::from_str(line, line.text)?;
+    if dot.x < 0 || dot.y < 0 {
+        return Err(line.error_line("expected a dot with non-negative coordinates"));
     }
+
+    Ok(dot)
 }
 
 #[derive(Debug)]
-enum Fold {
+pub enum Fold {
     X(i32),
     Y(i32),
 }
 
 impl Fold {
-    fn from_str(fold_str: &str) -> Fold {
-        let parts: Vec<&str> = fold_str.split('=').collect();
+    fn from_str(line: &SourceLine) -> Result<Fold, ParseError> {
+        let parts = match_pattern(
+            line,
+            "fold along {}={}",
+            "expected a fold of the form fold along x=N",
+        )?;
 
-        let dir: &str = parts[0].split("g ").collect::<Vec<&str>>()[1];
-        let num: i32 = parts[1].parse().unwrap();
+        let dir: &str = parts[0];
+        let num: u32 = line.parse(parts[1], "expected a fold line")?;
+        let num = num as i32;
 
         match dir {
-            "x" => Fold::X(num),
-            "y" => Fold::Y(num),
-            _ => panic!(),
+            "x" => Ok(Fold::X(num)),
+            "y" => Ok(Fold::Y(num)),
+            _ => Err(line.error(dir, "expected a fold axis of x or y")),
         }
     }
 }
 
-fn read_input() -> (Vec<Position>, Vec<Fold>) {
-    let stdin = io::stdin();
-
-    let mut positions: Vec<Position> = vec![];
-    let mut folds: Vec<Fold> = vec![];
-    let mut done_with_positions = false;
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-
-        if line.is_empty() {
-            done_with_positions = true;
-            continue;
-        }
-
-        if !done_with_positions {
-            positions.push(Position::from_str(&line));
-        } else {
-            folds.push(Fold::from_str(&line));
-        }
+fn read_input(input: &str) -> Result<(Vec<Point2>, Vec<Fold>), ParseError> {
+    let sections = sections(input);
+
+    let positions: Vec<Point2> = match sections.first() {
+        Some(lines) => lines.iter().map(read_dot).collect::<Result<_, _>>()?,
+        None => return Err(missing_line(1, "expected a dot position")),
+    };
+
+    let folds: Vec<Fold> = sections[1..]
+        .iter()
+        .flatten()
+        .map(Fold::from_str)
+        .collect::<Result<_, _>>()?;
+    if folds.is_empty() {
+        return Err(missing_line(input.lines().count() + 1, "expected a fold"));
     }
 
-    (positions, folds)
+    Ok((positions, folds))
 }
 
-fn get_num_dots_after_first_fold(positions: &[Position], folds: &[Fold]) -> i32 {
+fn get_num_dots_after_first_fold(positions: &[Point2], folds: &[Fold]) -> i32 {
     let paper = Paper::from_dots(positions);
     let paper = paper.fold(&folds[0]);
 
     paper.get_num_dots()
 }
 
-fn print_dots_after_folds(positions: &[Position], folds: &[Fold]) {
+fn get_dots_after_folds(positions: &[Point2], folds: &[Fold]) -> Paper {
     let mut paper = Paper::from_dots(positions);
 
     for fold in folds.iter() {
         paper = paper.fold(fold);
     }
 
-    paper.print();
+    paper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/13_example.txt")).unwrap();
+
+        assert_eq!(17, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/13_example.txt")).unwrap();
+
+        assert_eq!(
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....",
+            Answer::from(Solver::part_two(&input)).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = Solver::parse("6,10\n\nfold along z=3").err().unwrap();
+
+        assert_eq!((3, 12), (err.line, err.column));
+        assert_eq!("z", err.text);
+    }
 }
@@ -1,37 +1,54 @@
-use std::io;
-use std::io::prelude::*;
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::solution::Solution;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = get_power_consumption(&numbers);
+pub struct Solver;
 
-    println!("{}", answer);
-}
+impl Solution for Solver {
+    const DAY: u32 = 3;
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = get_life_support_rating(&numbers);
+    type Input = Vec<Vec<i32>>;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-    println!("{}", answer);
-}
+    fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+        read_input(input)
+    }
 
-fn read_input() -> Vec<Vec<i32>> {
-    let stdin = io::stdin();
+    fn part_one(numbers: &Vec<Vec<i32>>) -> i32 {
+        get_power_consumption(numbers)
+    }
+
+    fn part_two(numbers: &Vec<Vec<i32>>) -> i32 {
+        get_life_support_rating(numbers)
+    }
+}
 
+fn read_input(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
     let mut numbers: Vec<Vec<i32>> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<char> = line.chars().collect();
-
-        numbers.push(
-            parts
-                .iter()
-                .map(|c| c.to_string().parse().unwrap())
-                .collect(),
-        );
+    for line in SourceLine::all(input) {
+        let mut number: Vec<i32> = vec![];
+        for (i, c) in line.text.char_indices() {
+            match c {
+                '0' => number.push(0),
+                '1' => number.push(1),
+                _ => return Err(line.error_char(i, "expected a bit")),
+            }
+        }
+
+        if let Some(first) = numbers.first() {
+            if number.len() != first.len() {
+                return Err(line.error_line(&format!("expected {} bits", first.len())));
+            }
+        }
+
+        numbers.push(number);
+    }
+
+    if numbers.is_empty() || numbers[0].is_empty() {
+        return Err(missing_line(1, "expected a binary number"));
     }
 
-    numbers
+    Ok(numbers)
 }
 
 fn get_power_consumption(numbers: &[Vec<i32>]) -> i32 {
@@ -155,3 +172,25 @@ fn get_life_support_rating(numbers: &[Vec<i32>]) -> i32 {
 
     oxygen_generator_rating * co2_scrubber_rating
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(198, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(230, Solver::part_two(&input));
+    }
+}
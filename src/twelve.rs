@@ -1,19 +1,29 @@
-use std::collections::{HashMap, HashSet};
-use std::io;
-use std::io::prelude::*;
+use std::collections::HashMap;
 
-pub fn part_one() {
-    let graph = read_input();
-    let answer = calc_num_paths(&graph);
+use crate::error::{ParseError, SourceLine};
+use crate::search::count_paths;
+use crate::solution::Solution;
 
-    println!("{}", answer);
-}
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 12;
+
+    type Input = Graph;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Graph, ParseError> {
+        read_input(input)
+    }
 
-pub fn part_two() {
-    let graph = read_input();
-    let answer = calc_num_paths_allow_one_small_repeat(&graph);
+    fn part_one(graph: &Graph) -> i32 {
+        calc_num_paths(graph)
+    }
 
-    println!("{}", answer);
+    fn part_two(graph: &Graph) -> i32 {
+        calc_num_paths_allow_one_small_repeat(graph)
+    }
 }
 
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
@@ -39,7 +49,7 @@ impl Node {
 }
 
 #[derive(Debug)]
-struct Graph {
+pub struct Graph {
     edges: HashMap<Node, Vec<Node>>,
 }
 
@@ -69,99 +79,105 @@ impl Graph {
         Graph { edges }
     }
 
-    fn calc_num_paths(&self, src: Node, dst: Node, visited: &HashSet<Node>) -> i32 {
-        if src == dst {
-            return 1;
-        }
-
-        let neighbors = self.edges.get(&src);
-        if neighbors.is_none() {
-            return 0;
-        }
+    fn count_paths(&self, allow_small_repeat: bool) -> i32 {
+        let start = PathState {
+            node: Node::Start,
+            visited_small: vec![],
+            repeated_small: !allow_small_repeat,
+        };
+
+        count_paths(
+            start,
+            |state| state.node == Node::End,
+            |state| self.get_next_states(state),
+        ) as i32
+    }
 
-        let mut total = 0;
-        for n in neighbors.unwrap().iter() {
-            if *n == Node::Start {
-                continue;
-            } else if let Node::Small(_) = n {
-                if visited.contains(n) {
-                    continue;
+    fn get_next_states(&self, state: &PathState) -> Vec<PathState> {
+        let neighbors = match self.edges.get(&state.node) {
+            Some(neighbors) => neighbors,
+            None => return vec![],
+        };
+
+        neighbors
+            .iter()
+            .filter_map(|n| {
+                let mut repeated_small = state.repeated_small;
+                let mut visited_small = state.visited_small.clone();
+
+                match n {
+                    Node::Start => return None,
+                    Node::Small(_) if visited_small.contains(n) => {
+                        if repeated_small {
+                            return None;
+                        }
+                        repeated_small = true;
+                    }
+                    Node::Small(_) => visited_small.push(*n),
+                    _ => {}
                 }
-            }
-
-            let mut visited_2 = visited.clone();
-            visited_2.insert(src);
-
-            total += self.calc_num_paths(*n, dst, &visited_2);
-        }
 
-        total
+                Some(PathState {
+                    node: *n,
+                    visited_small,
+                    repeated_small,
+                })
+            })
+            .collect()
     }
+}
 
-    fn calc_num_paths_allow_one_small_repeat(
-        &self,
-        src: Node,
-        dst: Node,
-        visited: &HashMap<Node, i32>,
-        have_visited_small_twice: bool,
-    ) -> i32 {
-        if src == dst {
-            return 1;
-        }
+/// A partial path through the caves: where it is, which small caves it has passed through, and
+/// whether it has used up its one repeat visit to a small cave.
+struct PathState {
+    node: Node,
+    visited_small: Vec<Node>,
+    repeated_small: bool,
+}
 
-        let neighbors = self.edges.get(&src);
-        if neighbors.is_none() {
-            return 0;
+fn read_input(input: &str) -> Result<Graph, ParseError> {
+    let mut edges: Vec<(String, String)> = Vec::new();
+    for line in SourceLine::all(input) {
+        let parts: Vec<&str> = line.text.split('-').collect();
+        if parts.len() != 2 {
+            return Err(line.error_line("expected an edge of the form a-b"));
         }
 
-        let mut total = 0;
-        for n in neighbors.unwrap().iter() {
-            let mut have_visited_this_twice = have_visited_small_twice;
-            if *n == Node::Start {
-                continue;
-            } else if let Node::Small(_) = n {
-                if visited.get(n).is_some() && have_visited_small_twice {
-                    continue;
-                }
-
-                if visited.get(n).is_some() && *visited.get(n).unwrap() == 1 {
-                    have_visited_this_twice = true;
-                }
+        for part in parts.iter() {
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(line.error(part, "expected a cave name"));
             }
-
-            let mut visited_2 = visited.clone();
-            visited_2.insert(src, visited_2.get(&src).or(Some(&0)).unwrap() + 1);
-
-            total += self.calc_num_paths_allow_one_small_repeat(
-                *n,
-                dst,
-                &visited_2,
-                have_visited_this_twice,
-            );
         }
 
-        total
-    }
-}
-
-fn read_input() -> Graph {
-    let stdin = io::stdin();
-
-    let mut edges: Vec<(String, String)> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split('-').collect();
-
         edges.push((parts[0].to_string(), parts[1].to_string()));
     }
 
-    Graph::from_edges(&edges)
+    Ok(Graph::from_edges(&edges))
 }
 
 fn calc_num_paths(graph: &Graph) -> i32 {
-    graph.calc_num_paths(Node::Start, Node::End, &HashSet::new())
+    graph.count_paths(false)
 }
 
 fn calc_num_paths_allow_one_small_repeat(graph: &Graph) -> i32 {
-    graph.calc_num_paths_allow_one_small_repeat(Node::Start, Node::End, &HashMap::new(), false)
+    graph.count_paths(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_one_example_1() {
+        let input = Solver::parse(include_str!("../inputs/12_example_1.txt")).unwrap();
+
+        assert_eq!(10, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example_1() {
+        let input = Solver::parse(include_str!("../inputs/12_example_1.txt")).unwrap();
+
+        assert_eq!(36, Solver::part_two(&input));
+    }
 }
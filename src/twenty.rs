@@ -1,20 +1,74 @@
-use std::cmp;
-use std::collections::HashMap;
-use std::io;
-use std::io::prelude::*;
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::geometry::Point2;
+use crate::grid::Grid;
+use crate::params::Params;
+use crate::parsing::sections;
+use crate::solution::Solution;
+use crate::visualize::{Frames, Rgb, BLACK, WHITE};
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 20;
+    const PARAMS: &'static [&'static str] = &["steps"];
+
+    type Input = (IEAlgorithm, Image);
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<(IEAlgorithm, Image), ParseError> {
+        read_input(input)
+    }
 
-pub fn part_one() {
-    let (algorithm, image) = read_input();
-    let answer = solve_1(&algorithm, &image);
+    fn part_one(input: &(IEAlgorithm, Image)) -> i32 {
+        Self::part_one_with(input, &Params::default()).unwrap()
+    }
 
-    println!("{}", answer);
-}
+    fn part_two(input: &(IEAlgorithm, Image)) -> i32 {
+        Self::part_two_with(input, &Params::default()).unwrap()
+    }
+
+    fn part_one_with(input: &(IEAlgorithm, Image), params: &Params) -> Result<i32, String> {
+        let (algorithm, image) = input;
 
-pub fn part_two() {
-    let (algorithm, image) = read_input();
-    let answer = solve_2(&algorithm, &image);
+        Ok(get_num_filled_after(
+            algorithm,
+            image,
+            params.get("steps", 2)?,
+        ))
+    }
+
+    fn part_two_with(input: &(IEAlgorithm, Image), params: &Params) -> Result<i32, String> {
+        let (algorithm, image) = input;
 
-    println!("{}", answer);
+        Ok(get_num_filled_after(
+            algorithm,
+            image,
+            params.get("steps", 50)?,
+        ))
+    }
+
+    fn visualize(
+        input: &(IEAlgorithm, Image),
+        part: &str,
+        params: &Params,
+        frames: &mut Frames,
+    ) -> Result<(), String> {
+        let (algorithm, image) = input;
+        let steps: u32 = match part {
+            "one" => params.get("steps", 2)?,
+            _ => params.get("steps", 50)?,
+        };
+
+        let mut image = image.clone();
+        frames.write(&image.pixels, |c| c.to_rgb())?;
+        for _ in 0..steps {
+            image = algorithm.apply(&image);
+            frames.write(&image.pixels, |c| c.to_rgb())?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -24,15 +78,31 @@ enum Color {
 }
 
 impl Color {
-    fn from_char(color_char: char) -> Color {
+    fn from_char(color_char: char) -> Option<Color> {
         match color_char {
-            '#' => Color::Filled,
-            '.' => Color::Empty,
-            _ => panic!(),
+            '#' => Some(Color::Filled),
+            '.' => Some(Color::Empty),
+            _ => None,
         }
     }
 
-    fn to_char(self) -> char {
+    fn from_line(line: &SourceLine) -> Result<Vec<Color>, ParseError> {
+        line.text
+            .char_indices()
+            .map(|(i, c)| {
+                Color::from_char(c).ok_or_else(|| line.error_char(i, "expected a pixel of # or ."))
+            })
+            .collect()
+    }
+
+    fn to_rgb(self) -> Rgb {
+        match self {
+            Color::Filled => WHITE,
+            Color::Empty => BLACK,
+        }
+    }
+
+    fn _to_char(self) -> char {
         match self {
             Color::Filled => '#',
             Color::Empty => '.',
@@ -41,35 +111,39 @@ impl Color {
 }
 
 #[derive(Debug)]
-struct IEAlgorithm {
+pub struct IEAlgorithm {
     settings: Vec<Color>,
 }
 
 impl IEAlgorithm {
-    fn from_str(algorithm_str: &str) -> IEAlgorithm {
-        let settings = algorithm_str.chars().map(Color::from_char).collect();
+    fn from_str(line: &SourceLine) -> Result<IEAlgorithm, ParseError> {
+        let settings = Color::from_line(line)?;
+        if settings.len() != 512 {
+            return Err(line.error_line("expected an algorithm of 512 pixels"));
+        }
 
-        IEAlgorithm { settings }
+        Ok(IEAlgorithm { settings })
     }
 
     fn apply(&self, image: &Image) -> Image {
-        let mut new_image = Image::new();
+        let width = image.pixels.width() + 2;
+        let height = image.pixels.height() + 2;
+        let mut pixels = Grid::new(width, height, Color::Empty);
 
-        for pos in image.get_dimensions().expanded(1).positions() {
-            let current_values: [Color; 9] = image.get_neighborhood(&pos);
+        for (x, y) in pixels.positions() {
+            let center = Point2::new(x as i32 - 1, y as i32 - 1);
+            let current_values = image.get_neighborhood(center);
 
             let lookup_value = IEAlgorithm::calc_lookup_value(&current_values);
-            let new_value = self.settings[lookup_value];
-
-            new_image.set_pixel(&pos, new_value);
+            pixels[(x, y)] = self.settings[lookup_value];
         }
 
-        new_image.set_beyond(match image.get_beyond() {
+        let beyond = match image.beyond {
             Color::Empty => self.settings[0],
             Color::Filled => self.settings[511],
-        });
+        };
 
-        new_image
+        Image { pixels, beyond }
     }
 
     fn calc_lookup_value(values: &[Color]) -> usize {
@@ -87,200 +161,113 @@ impl IEAlgorithm {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn new(x: i32, y: i32) -> Position {
-        Position { x, y }
-    }
-
-    fn added(&self, other: &Position) -> Position {
-        Position::new(self.x + other.x, self.y + other.y)
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-struct PixelRange {
-    min: Position,
-    max: Position,
-}
-
-impl PixelRange {
-    fn new(min: &Position, max: &Position) -> PixelRange {
-        PixelRange {
-            min: *min,
-            max: *max,
-        }
-    }
-
-    fn expanded(&self, expand_amount: i32) -> PixelRange {
-        let new_min = self
-            .min
-            .added(&Position::new(-expand_amount, -expand_amount));
-        let new_max = self.max.added(&Position::new(expand_amount, expand_amount));
-
-        PixelRange::new(&new_min, &new_max)
-    }
-
-    fn positions(&self) -> Vec<Position> {
-        let mut positions = vec![];
-
-        for y in self.min.y..=self.max.y {
-            for x in self.min.x..=self.max.x {
-                positions.push(Position::new(x, y));
-            }
-        }
-
-        positions
-    }
-}
-
+/// An image surrounded by an infinite area of pixels that all share the `beyond` color.
 #[derive(Clone, Debug)]
-struct Image {
-    pixels: HashMap<Position, Color>,
+pub struct Image {
+    pixels: Grid<Color>,
     beyond: Color,
 }
 
 impl Image {
-    fn new() -> Image {
-        Image {
-            pixels: HashMap::new(),
+    fn from_lines(lines: &[SourceLine]) -> Result<Image, ParseError> {
+        let pixels = Grid::from_lines(lines, "expected a pixel of # or .", Color::from_char)?;
+
+        Ok(Image {
+            pixels,
             beyond: Color::Empty,
-        }
+        })
     }
 
-    fn from_lines(lines: &[String]) -> Image {
-        let mut pixels = HashMap::new();
+    fn get_pixel(&self, position: Point2) -> Color {
+        *self
+            .pixels
+            .get_signed(position.x as isize, position.y as isize)
+            .unwrap_or(&self.beyond)
+    }
 
-        for (y, line) in lines.iter().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                let position = Position::new(x as i32, y as i32);
-                let value = Color::from_char(c);
+    fn get_neighborhood(&self, center: Point2) -> [Color; 9] {
+        let mut pixels = [Color::Empty; 9];
+        for (i, pixel) in pixels.iter_mut().enumerate() {
+            let offset = Point2::new((i % 3) as i32 - 1, (i / 3) as i32 - 1);
 
-                pixels.insert(position, value);
-            }
+            *pixel = self.get_pixel(center + offset);
         }
 
-        Image {
-            pixels,
-            beyond: Color::Empty,
-        }
+        pixels
     }
 
-    fn get_pixel(&self, position: &Position) -> Color {
-        *self.pixels.get(position).unwrap_or(&self.beyond)
+    fn get_num_filled(&self) -> i32 {
+        self.pixels
+            .values()
+            .filter(|c| **c == Color::Filled)
+            .count() as i32
     }
 
-    fn get_neighborhood(&self, center: &Position) -> [Color; 9] {
-        let pixels: Vec<Color> = PixelRange::new(center, center)
-            .expanded(1)
-            .positions()
-            .iter()
-            .map(|p| self.get_pixel(p))
-            .collect();
-
-        [
-            pixels[0], pixels[1], pixels[2], pixels[3], pixels[4], pixels[5], pixels[6], pixels[7],
-            pixels[8],
-        ]
+    fn _print(&self) {
+        println!("{}", self.pixels.render(|c| c._to_char()));
     }
+}
 
-    fn get_dimensions(&self) -> PixelRange {
-        let mut min_x = 99999999;
-        let mut max_x = -99999999;
-        let mut min_y = 99999999;
-        let mut max_y = -99999999;
-
-        for p in self.pixels.keys() {
-            min_x = cmp::min(min_x, p.x);
-            max_x = cmp::max(max_x, p.x);
+fn read_input(input: &str) -> Result<(IEAlgorithm, Image), ParseError> {
+    let sections = sections(input);
 
-            min_y = cmp::min(min_y, p.y);
-            max_y = cmp::max(max_y, p.y);
-        }
+    let algorithm = match sections.first() {
+        Some(lines) => IEAlgorithm::from_str(&lines[0])?,
+        None => return Err(missing_line(1, "expected an image enhancement algorithm")),
+    };
 
-        let dims_min = Position::new(min_x, min_y);
-        let dims_max = Position::new(max_x, max_y);
-
-        PixelRange::new(&dims_min, &dims_max)
+    let image_lines: Vec<SourceLine> = sections.iter().flatten().skip(1).copied().collect();
+    if image_lines.is_empty() {
+        return Err(missing_line(
+            input.lines().count() + 1,
+            "expected an input image",
+        ));
     }
 
-    fn set_pixel(&mut self, position: &Position, color: Color) {
-        self.pixels.insert(*position, color);
-    }
+    let image = Image::from_lines(&image_lines)?;
 
-    fn get_beyond(&self) -> Color {
-        self.beyond
-    }
+    Ok((algorithm, image))
+}
 
-    fn set_beyond(&mut self, value: Color) {
-        self.beyond = value;
-    }
+fn get_num_filled_after(algorithm: &IEAlgorithm, image: &Image, steps: u32) -> i32 {
+    let mut image: Image = image.clone();
 
-    fn print(&self) {
-        let dims = self.get_dimensions();
-        for y in dims.min.y..=dims.max.y {
-            for x in dims.min.x..=dims.max.x {
-                let pos = Position::new(x, y);
-                print!("{}", self.get_pixel(&pos).to_char());
-            }
-            println!();
-        }
+    for _ in 0..steps {
+        image = algorithm.apply(&image);
     }
-}
 
-fn read_input() -> (IEAlgorithm, Image) {
-    let stdin = io::stdin();
-
-    let mut algorithm: Option<IEAlgorithm> = None;
-    let mut image_lines: Vec<String> = vec![];
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
+    image.get_num_filled()
+}
 
-        if line.is_empty() {
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if algorithm.is_none() {
-            algorithm = Some(IEAlgorithm::from_str(&line));
-            continue;
-        }
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/20_example.txt")).unwrap();
 
-        image_lines.push(line.to_string());
+        assert_eq!(35, Solver::part_one(&input));
     }
 
-    let image = Image::from_lines(&image_lines);
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/20_example.txt")).unwrap();
 
-    (algorithm.unwrap(), image)
-}
-
-fn solve_1(algorithm: &IEAlgorithm, image: &Image) -> i32 {
-    let mut image: Image = image.clone();
+        assert_eq!(3351, Solver::part_two(&input));
+    }
 
-    image = algorithm.apply(&image);
-    image = algorithm.apply(&image);
+    #[test]
+    fn part_one_test_zero() {
+        let input = Solver::parse(include_str!("../inputs/20_test_zero.txt")).unwrap();
 
-    image
-        .pixels
-        .values()
-        .filter(|c| **c == Color::Filled)
-        .count() as i32
-}
+        assert_eq!(40, Solver::part_one(&input));
+    }
 
-fn solve_2(algorithm: &IEAlgorithm, image: &Image) -> i32 {
-    let mut image: Image = image.clone();
+    #[test]
+    fn part_two_test_zero() {
+        let input = Solver::parse(include_str!("../inputs/20_test_zero.txt")).unwrap();
 
-    for _ in 0..50 {
-        image = algorithm.apply(&image);
+        assert_eq!(4569, Solver::part_two(&input));
     }
-
-    image
-        .pixels
-        .values()
-        .filter(|c| **c == Color::Filled)
-        .count() as i32
 }
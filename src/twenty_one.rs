@@ -1,21 +1,69 @@
 use std::cmp;
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
-use std::io;
-use std::io::prelude::*;
 
-pub fn part_one() {
-    let mut state = read_input();
-    let answer = solve_1(&mut state);
+use num_bigint::BigInt;
 
-    println!("{}", answer);
+use crate::error::{missing_line, ParseError, SourceLine};
+use crate::params::Params;
+use crate::parsing::match_pattern;
+use crate::solution::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 21;
+    const PARAMS: &'static [&'static str] = &["score", "sides"];
+
+    type Input = GameState;
+    type PartOne = i32;
+    type PartTwo = BigInt;
+
+    fn parse(input: &str) -> Result<GameState, ParseError> {
+        read_input(input)
+    }
+
+    fn part_one(state: &GameState) -> i32 {
+        Self::part_one_with(state, &Params::default()).unwrap()
+    }
+
+    fn part_two(state: &GameState) -> BigInt {
+        Self::part_two_with(state, &Params::default()).unwrap()
+    }
+
+    fn part_one_with(state: &GameState, params: &Params) -> Result<i32, String> {
+        let rules = Rules::from_params(params, 1000, 100)?;
+        let mut state = state.clone();
+
+        Ok(solve_1(&mut state, &rules))
+    }
+
+    fn part_two_with(state: &GameState, params: &Params) -> Result<BigInt, String> {
+        let rules = Rules::from_params(params, 21, 3)?;
+
+        Ok(solve_2(state, &rules))
+    }
 }
 
-pub fn part_two() {
-    let state = read_input();
-    let answer = solve_2(&state);
+/// The score needed to win, and the number of sides on the die, which is numbered from 1.
+struct Rules {
+    winning_score: i32,
+    die_sides: i32,
+}
+
+impl Rules {
+    fn from_params(params: &Params, winning_score: i32, die_sides: i32) -> Result<Rules, String> {
+        let winning_score = params.get("score", winning_score)?;
+        let die_sides = params.get("sides", die_sides)?;
+        if die_sides < 1 {
+            return Err("sides must be at least 1".to_string());
+        }
 
-    println!("{}", answer);
+        Ok(Rules {
+            winning_score,
+            die_sides,
+        })
+    }
 }
 
 type PlayerId = i32;
@@ -35,11 +83,11 @@ impl QuantumnState {
         }
     }
 
-    fn get_children(&self) -> Vec<QuantumnState> {
+    fn get_children(&self, die_sides: i32) -> Vec<QuantumnState> {
         let mut children: Vec<QuantumnState> = vec![];
-        for r_1 in 1..=3 {
-            for r_2 in 1..=3 {
-                for r_3 in 1..=3 {
+        for r_1 in 1..=die_sides {
+            for r_2 in 1..=die_sides {
+                for r_3 in 1..=die_sides {
                     let mut state = self.clone();
 
                     state
@@ -66,13 +114,15 @@ trait Dice {
 }
 
 struct DeterministicDice {
+    sides: i32,
     next_value: i32,
     num_times_rolled: i32,
 }
 
 impl DeterministicDice {
-    fn new() -> DeterministicDice {
+    fn new(sides: i32) -> DeterministicDice {
         DeterministicDice {
+            sides,
             next_value: 1,
             num_times_rolled: 0,
         }
@@ -84,7 +134,7 @@ impl Dice for DeterministicDice {
         let value = self.next_value;
 
         self.next_value += 1;
-        if self.next_value > 100 {
+        if self.next_value > self.sides {
             self.next_value = 1;
         }
 
@@ -99,30 +149,52 @@ impl Dice for DeterministicDice {
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct GameState {
+pub struct GameState {
     player_positions: BTreeMap<PlayerId, Position>,
     scores: BTreeMap<PlayerId, i32>,
 }
 
 impl GameState {
-    fn from_lines(lines: &[String]) -> GameState {
+    fn from_lines(lines: &[SourceLine]) -> Result<GameState, ParseError> {
         let mut player_positions: BTreeMap<PlayerId, Position> = BTreeMap::new();
         let mut scores: BTreeMap<PlayerId, i32> = BTreeMap::new();
 
+        if lines.len() > 2 {
+            return Err(lines[2].error_line("expected only two players"));
+        }
+
         for line in lines.iter() {
-            let parts: Vec<&str> = line.split(' ').collect();
+            let parts = match_pattern(
+                line,
+                "Player {} starting position: {}",
+                "expected a line of the form Player N starting position: P",
+            )?;
+
+            let player_id: PlayerId = line.parse(parts[0], "expected a player number")?;
+            if player_id != line.number as PlayerId {
+                return Err(line.error(parts[0], "expected players to be numbered in order from 1"));
+            }
 
-            let player_id: PlayerId = parts[1].parse().unwrap();
-            let position: Position = parts[4].parse().unwrap();
+            let position: Position = line.parse(parts[1], "expected a starting position")?;
+            if !(1..=10).contains(&position) {
+                return Err(line.error(parts[1], "expected a starting position from 1 to 10"));
+            }
 
             player_positions.insert(player_id, position);
             scores.insert(player_id, 0);
         }
 
-        GameState {
+        if lines.len() < 2 {
+            return Err(missing_line(
+                lines.len() + 1,
+                "expected a starting position for two players",
+            ));
+        }
+
+        Ok(GameState {
             player_positions,
             scores,
-        }
+        })
     }
 
     fn advance(&mut self, dice: &mut Box<dyn Dice>, player: PlayerId) {
@@ -151,23 +223,17 @@ impl GameState {
     }
 }
 
-fn read_input() -> GameState {
-    let stdin = io::stdin();
-
-    let lines: Vec<String> = stdin
-        .lock()
-        .lines()
-        .map(|l| l.unwrap().to_string())
-        .collect();
+fn read_input(input: &str) -> Result<GameState, ParseError> {
+    let lines: Vec<SourceLine> = SourceLine::all(input).collect();
 
     GameState::from_lines(&lines)
 }
 
-fn solve_1(state: &mut GameState) -> i32 {
-    let mut dice: Box<dyn Dice> = Box::new(DeterministicDice::new());
+fn solve_1(state: &mut GameState, rules: &Rules) -> i32 {
+    let mut dice: Box<dyn Dice> = Box::new(DeterministicDice::new(rules.die_sides));
 
     let mut current_player = *state.scores.keys().min().unwrap();
-    while *state.scores.values().max().unwrap() < 1000 {
+    while *state.scores.values().max().unwrap() < rules.winning_score {
         state.advance(&mut dice, current_player);
 
         current_player += 1;
@@ -186,44 +252,73 @@ fn solve_1(state: &mut GameState) -> i32 {
     losing_player_score * dice.get_num_times_rolled()
 }
 
-fn add_results(a: (u64, u64), b: (u64, u64)) -> (u64, u64) {
+fn add_results(a: (BigInt, BigInt), b: (BigInt, BigInt)) -> (BigInt, BigInt) {
     (a.0 + b.0, a.1 + b.1)
 }
 
 fn calc_records(
     state: &QuantumnState,
-    cache: &mut HashMap<QuantumnState, (u64, u64)>,
-) -> (u64, u64) {
+    rules: &Rules,
+    cache: &mut HashMap<QuantumnState, (BigInt, BigInt)>,
+) -> (BigInt, BigInt) {
     if let Entry::Occupied(records) = cache.entry(state.clone()) {
-        return *records.get();
+        return records.get().clone();
     }
 
-    if *state.game_state.scores.values().max().unwrap() >= 21 {
+    if *state.game_state.scores.values().max().unwrap() >= rules.winning_score {
         let prev_player = (state.current_player) % 2 + 1;
 
         return match prev_player {
-            1 => (1, 0),
-            2 => (0, 1),
+            1 => (BigInt::from(1), BigInt::from(0)),
+            2 => (BigInt::from(0), BigInt::from(1)),
             _ => panic!(),
         };
     }
 
     let records = state
-        .get_children()
+        .get_children(rules.die_sides)
         .iter()
-        .map(|child| calc_records(child, cache))
-        .fold((0, 0), add_results);
+        .map(|child| calc_records(child, rules, cache))
+        .fold((BigInt::from(0), BigInt::from(0)), add_results);
 
-    cache.insert(state.clone(), records);
+    cache.insert(state.clone(), records.clone());
 
     records
 }
 
-fn solve_2(state: &GameState) -> u64 {
+fn solve_2(state: &GameState, rules: &Rules) -> BigInt {
     let state = QuantumnState::new(state, 1);
 
-    let mut cache: HashMap<QuantumnState, (u64, u64)> = HashMap::new();
-    let records = calc_records(&state, &mut cache);
+    let mut cache: HashMap<QuantumnState, (BigInt, BigInt)> = HashMap::new();
+    let records = calc_records(&state, rules, &mut cache);
 
     cmp::max(records.0, records.1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::read_params;
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(include_str!("../inputs/21_example.txt")).unwrap();
+
+        assert_eq!(739785, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(include_str!("../inputs/21_example.txt")).unwrap();
+
+        assert_eq!(BigInt::from(444356092776315u64), Solver::part_two(&input));
+    }
+
+    #[test]
+    fn universes_beyond_u64() {
+        let input = Solver::parse(include_str!("../inputs/21_example.txt")).unwrap();
+        let params = read_params(vec!["score=15", "sides=4"]).unwrap();
+
+        assert!(Solver::part_two_with(&input, &params).unwrap() > BigInt::from(u64::MAX));
+    }
+}
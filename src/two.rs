@@ -1,45 +1,115 @@
-use std::io;
-use std::io::prelude::*;
+use std::str::FromStr;
 
-pub fn part_one() {
-    let numbers = read_input();
-    let answer = get_final_position(&numbers);
+use crate::error::{ParseError, SourceLine};
+use crate::params::Params;
+use crate::solution::{RunError, Solution};
 
-    println!("{}", answer.depth * answer.horizontal);
-}
+pub struct Solver;
+
+impl Solution for Solver {
+    const DAY: u32 = 2;
+    const PARAMS: &'static [&'static str] = &["bad_lines"];
+
+    type Input = Vec<Command>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Vec<Command>, ParseError> {
+        let (commands, _) = read_commands(input, BadLines::Reject)?;
+
+        Ok(commands)
+    }
+
+    fn parse_with(
+        input: &str,
+        params: &Params,
+        warnings: &mut Vec<ParseError>,
+    ) -> Result<Vec<Command>, RunError> {
+        let bad_lines = params
+            .get("bad_lines", BadLines::Reject)
+            .map_err(RunError::Params)?;
+        let (commands, skipped) = read_commands(input, bad_lines).map_err(RunError::Parse)?;
+        warnings.extend(skipped);
+
+        Ok(commands)
+    }
+
+    fn part_one(commands: &Vec<Command>) -> i32 {
+        let answer = get_final_position(&Naive, commands);
+
+        answer.depth * answer.horizontal
+    }
 
-pub fn part_two() {
-    let numbers = read_input();
-    let answer = get_final_position_2(&numbers);
+    fn part_two(commands: &Vec<Command>) -> i32 {
+        let answer = get_final_position(&Aim, commands);
 
-    println!("{}", answer.depth * answer.horizontal);
+        answer.depth * answer.horizontal
+    }
 }
 
-fn read_input() -> Vec<(String, i32)> {
-    let stdin = io::stdin();
+/// What to do with a line that is not a valid command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BadLines {
+    /// Stop at the first bad line and return its error.
+    Reject,
+    /// Leave bad lines out of the commands, returning their errors alongside them.
+    Skip,
+}
 
-    let mut commands: Vec<(String, i32)> = Vec::new();
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split(' ').collect();
-        let direction = parts[0].to_string();
-        let number: i32 = parts[1].parse().unwrap();
+impl FromStr for BadLines {
+    type Err = String;
 
-        commands.push((direction, number));
+    fn from_str(text: &str) -> Result<BadLines, String> {
+        match text {
+            "reject" => Ok(BadLines::Reject),
+            "skip" => Ok(BadLines::Skip),
+            _ => Err(format!(
+                "unknown bad_lines {}, expected reject or skip",
+                text
+            )),
+        }
     }
+}
+
+/// Parses every command, along with the errors for any lines that were skipped.
+pub fn read_commands<V: Verb>(
+    input: &str,
+    bad_lines: BadLines,
+) -> Result<(Vec<Command<V>>, Vec<ParseError>), ParseError> {
+    let mut commands: Vec<Command<V>> = Vec::new();
+    let mut skipped: Vec<ParseError> = Vec::new();
+    for line in SourceLine::all(input) {
+        match Command::from_line(&line) {
+            Ok(command) => commands.push(command),
+            Err(err) if bad_lines == BadLines::Skip => skipped.push(err),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok((commands, skipped))
+}
+
+/// The word at the start of a command. Models that understand other commands than the puzzle's
+/// can define their own verbs and read them with `read_commands`.
+pub trait Verb: Sized {
+    /// The error reported for a line that starts with a word that is not one of these verbs.
+    const EXPECTED: &'static str;
 
-    commands
+    fn from_word(word: &str) -> Option<Self>;
 }
 
-enum Direction {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
     Up,
     Down,
     Forward,
 }
 
-impl Direction {
-    fn from_str(direction_str: &str) -> Option<Direction> {
-        match direction_str {
+impl Verb for Direction {
+    const EXPECTED: &'static str = "expected a direction of forward, down, or up";
+
+    fn from_word(word: &str) -> Option<Direction> {
+        match word {
             "up" => Some(Direction::Up),
             "down" => Some(Direction::Down),
             "forward" => Some(Direction::Forward),
@@ -48,63 +118,285 @@ impl Direction {
     }
 }
 
-struct Position {
-    depth: i32,
-    horizontal: i32,
-    aim: i32,
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Command<V = Direction> {
+    pub direction: V,
+    pub distance: i32,
+}
+
+impl<V: Verb> Command<V> {
+    fn from_line(line: &SourceLine) -> Result<Command<V>, ParseError> {
+        let parts: Vec<&str> = line.text.split(' ').collect();
+        if parts.len() != 2 {
+            return Err(line.error_line("expected a direction and a distance"));
+        }
+
+        let direction = V::from_word(parts[0]).ok_or_else(|| line.error(parts[0], V::EXPECTED))?;
+
+        let distance: i32 = line.parse(parts[1], "expected a distance")?;
+        if distance < 0 {
+            return Err(line.error(parts[1], "expected a distance that is not negative"));
+        }
+
+        Ok(Command {
+            direction,
+            distance,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Position {
+    pub depth: i32,
+    pub horizontal: i32,
+    pub aim: i32,
 }
 
-impl Position {
-    fn move_direction(&mut self, direction: Direction, distance: i32) {
+/// A way of interpreting commands, which decides what state the submarine is in and how each
+/// command changes it.
+pub trait MovementModel {
+    type Verb: Copy;
+    type State: Copy + Default;
+
+    fn apply(&self, state: &mut Self::State, direction: Self::Verb, distance: i32);
+}
+
+/// Moves straight up, down, or forward by the distance given.
+pub struct Naive;
+
+impl MovementModel for Naive {
+    type Verb = Direction;
+    type State = Position;
+
+    fn apply(&self, position: &mut Position, direction: Direction, distance: i32) {
         match direction {
-            Direction::Up => self.depth -= distance,
-            Direction::Down => self.depth += distance,
-            Direction::Forward => self.horizontal += distance,
+            Direction::Up => position.depth -= distance,
+            Direction::Down => position.depth += distance,
+            Direction::Forward => position.horizontal += distance,
         }
     }
+}
 
-    fn move_direction_2(&mut self, direction: Direction, distance: i32) {
+/// Turns the submarine up and down, and only changes depth while moving forward.
+pub struct Aim;
+
+impl MovementModel for Aim {
+    type Verb = Direction;
+    type State = Position;
+
+    fn apply(&self, position: &mut Position, direction: Direction, distance: i32) {
         match direction {
-            Direction::Up => self.aim -= distance,
-            Direction::Down => self.aim += distance,
+            Direction::Up => position.aim -= distance,
+            Direction::Down => position.aim += distance,
             Direction::Forward => {
-                self.horizontal += distance;
-                self.depth += self.aim * distance;
+                position.horizontal += distance;
+                position.depth += position.aim * distance;
             }
         }
     }
 }
 
-fn get_final_position(moves: &[(String, i32)]) -> Position {
-    let mut position = Position {
-        depth: 0,
-        horizontal: 0,
-        aim: 0,
-    };
+/// Runs the commands from the model's default state, returning that state followed by the state
+/// after each command.
+pub fn trajectory<M: MovementModel>(model: &M, commands: &[Command<M::Verb>]) -> Vec<M::State> {
+    let mut state = M::State::default();
+    let mut states = vec![state];
+
+    for command in commands.iter() {
+        model.apply(&mut state, command.direction, command.distance);
+        states.push(state);
+    }
+
+    states
+}
+
+/// Writes a trajectory as CSV, with one row for the starting position and each command after it.
+pub fn to_csv(positions: &[Position]) -> String {
+    let mut csv = "step,horizontal,depth,aim\n".to_string();
+    for (step, position) in positions.iter().enumerate() {
+        csv += &format!(
+            "{},{},{},{}\n",
+            step, position.horizontal, position.depth, position.aim
+        );
+    }
+
+    csv
+}
 
-    for m in moves.iter() {
-        let direction = Direction::from_str(&m.0).unwrap();
-        let distance = m.1;
+/// The deepest point of a trajectory, and the first step where it was reached.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CourseStats {
+    pub max_depth: i32,
+    pub max_depth_step: usize,
+}
 
-        position.move_direction(direction, distance);
+impl CourseStats {
+    pub fn from_trajectory(positions: &[Position]) -> CourseStats {
+        let mut stats = CourseStats {
+            max_depth: 0,
+            max_depth_step: 0,
+        };
+
+        for (step, position) in positions.iter().enumerate() {
+            if step == 0 || position.depth > stats.max_depth {
+                stats.max_depth = position.depth;
+                stats.max_depth_step = step;
+            }
+        }
+
+        stats
     }
+}
 
-    position
+fn get_final_position<M>(model: &M, commands: &[Command]) -> Position
+where
+    M: MovementModel<Verb = Direction, State = Position>,
+{
+    *trajectory(model, commands).last().unwrap()
 }
 
-fn get_final_position_2(moves: &[(String, i32)]) -> Position {
-    let mut position = Position {
-        depth: 0,
-        horizontal: 0,
-        aim: 0,
-    };
+/// Runs the commands with the movement model of the given part, returning the submarine's whole
+/// trajectory.
+pub fn get_course(commands: &[Command], part: &str) -> Result<Vec<Position>, String> {
+    match part {
+        "one" => Ok(trajectory(&Naive, commands)),
+        "two" => Ok(trajectory(&Aim, commands)),
+        p => Err(format!("Unknown part: {}", p)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::read_params;
+    use crate::solution::run_part_one;
+
+    const EXAMPLE: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+    #[test]
+    fn part_one_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(150, Solver::part_one(&input));
+    }
+
+    #[test]
+    fn part_two_example() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(900, Solver::part_two(&input));
+    }
+
+    #[test]
+    fn aim_trajectory() {
+        let input = Solver::parse(EXAMPLE).unwrap();
+        let positions = get_course(&input, "two").unwrap();
 
-    for m in moves.iter() {
-        let direction = Direction::from_str(&m.0).unwrap();
-        let distance = m.1;
+        assert_eq!(7, positions.len());
+        assert_eq!(Position::default(), positions[0]);
+        assert_eq!(
+            Position {
+                depth: 40,
+                horizontal: 13,
+                aim: 5,
+            },
+            positions[3]
+        );
+    }
+
+    #[test]
+    fn trajectory_output() {
+        let input = Solver::parse("forward 5\ndown 5\nforward 8\nup 3\n").unwrap();
+        let positions = trajectory(&Naive, &input);
+
+        assert_eq!(
+            "step,horizontal,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,5,0\n3,13,5,0\n4,13,2,0\n",
+            to_csv(&positions)
+        );
+        assert_eq!(
+            CourseStats {
+                max_depth: 5,
+                max_depth_step: 2,
+            },
+            CourseStats::from_trajectory(&positions)
+        );
+    }
+
+    #[test]
+    fn bad_commands() {
+        let err = Solver::parse("forward 5\nbackward 3\n").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+
+        let err = Solver::parse("down -2\n").unwrap_err();
+        assert_eq!((1, 6), (err.line, err.column));
+        assert!(Solver::parse("up x\n").is_err());
+
+        let (commands, skipped) =
+            read_commands::<Direction>("forward 5\nup\ndown 2\n", BadLines::Skip).unwrap();
+        assert_eq!(2, commands.len());
+        assert_eq!(1, skipped.len());
+        assert_eq!(2, skipped[0].line);
+
+        let params = read_params(vec!["bad_lines=skip"]).unwrap();
+        let result = run_part_one::<Solver>("forward 5\nup\ndown 2\n", &params).unwrap();
+        assert_eq!("10", result.answer.to_string());
+        assert_eq!(1, result.warnings.len());
+        assert_eq!(Some(2), result.warnings[0].day);
+        assert!(run_part_one::<Solver>("up\n", &Params::default()).is_err());
+    }
+
+    #[derive(Clone, Copy)]
+    enum Thrust {
+        Forward,
+        Backward,
+        Up,
+        Down,
+        Left,
+        Right,
+    }
 
-        position.move_direction_2(direction, distance);
+    impl Verb for Thrust {
+        const EXPECTED: &'static str = "expected a direction to thrust in";
+
+        fn from_word(word: &str) -> Option<Thrust> {
+            match word {
+                "forward" => Some(Thrust::Forward),
+                "backward" => Some(Thrust::Backward),
+                "up" => Some(Thrust::Up),
+                "down" => Some(Thrust::Down),
+                "left" => Some(Thrust::Left),
+                "right" => Some(Thrust::Right),
+                _ => None,
+            }
+        }
     }
 
-    position
+    /// Moves freely in three dimensions, as (horizontal, depth, sideways).
+    struct Free;
+
+    impl MovementModel for Free {
+        type Verb = Thrust;
+        type State = (i32, i32, i32);
+
+        fn apply(&self, state: &mut (i32, i32, i32), direction: Thrust, distance: i32) {
+            match direction {
+                Thrust::Forward => state.0 += distance,
+                Thrust::Backward => state.0 -= distance,
+                Thrust::Up => state.1 -= distance,
+                Thrust::Down => state.1 += distance,
+                Thrust::Left => state.2 -= distance,
+                Thrust::Right => state.2 += distance,
+            }
+        }
+    }
+
+    #[test]
+    fn custom_model() {
+        let (commands, _) =
+            read_commands::<Thrust>("forward 5\nleft 2\nbackward 3\ndown 4\n", BadLines::Reject)
+                .unwrap();
+
+        assert_eq!(Some(&(2, 4, -2)), trajectory(&Free, &commands).last());
+        assert!(read_commands::<Thrust>("sideways 1\n", BadLines::Reject).is_err());
+    }
 }
@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::grid::Grid;
+
+/// A color as red, green, and blue components.
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const RED: Rgb = [220, 40, 40];
+
+/// Colors for telling apart neighboring regions, such as basins.
+pub const PALETTE: [Rgb; 6] = [
+    [230, 159, 0],
+    [86, 180, 233],
+    [0, 158, 115],
+    [240, 228, 66],
+    [0, 114, 178],
+    [204, 121, 167],
+];
+
+/// Small grids are scaled up until their frames are at least this many pixels across.
+const MIN_FRAME_SIZE: usize = 400;
+
+/// Encodes a grid as a binary PPM image, drawing each cell as a `scale` by `scale` square.
+pub fn to_ppm<T, F>(grid: &Grid<T>, scale: usize, color: F) -> Vec<u8>
+where
+    F: Fn(&T) -> Rgb,
+{
+    let width = grid.width() * scale;
+    let height = grid.height() * scale;
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    image.reserve(width * height * 3);
+    for row in grid.rows() {
+        let colors: Vec<Rgb> = row.iter().map(&color).collect();
+        for _ in 0..scale {
+            for rgb in colors.iter() {
+                for _ in 0..scale {
+                    image.extend_from_slice(rgb);
+                }
+            }
+        }
+    }
+
+    image
+}
+
+/// Blends between two colors, where a `t` of 0.0 gives `from` and 1.0 gives `to`.
+pub fn gradient(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let mut rgb = [0; 3];
+    for (i, value) in rgb.iter_mut().enumerate() {
+        *value = (from[i] as f64 + (to[i] as f64 - from[i] as f64) * t).round() as u8;
+    }
+
+    rgb
+}
+
+/// Writes a sequence of grids as numbered PPM frames in a directory.
+pub struct Frames {
+    dir: PathBuf,
+    count: usize,
+}
+
+impl Frames {
+    /// Creates the directory for the frames if it does not already exist.
+    pub fn create(dir: &Path) -> Result<Frames, String> {
+        fs::create_dir_all(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+
+        Ok(Frames {
+            dir: dir.to_path_buf(),
+            count: 0,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes a grid as the next frame, returning the path it was written to.
+    pub fn write<T, F>(&mut self, grid: &Grid<T>, color: F) -> Result<PathBuf, String>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let largest = grid.width().max(grid.height()).max(1);
+        let scale = (MIN_FRAME_SIZE / largest).max(1);
+
+        let path = self.dir.join(format!("frame_{:05}.ppm", self.count));
+        fs::write(&path, to_ppm(grid, scale, color))
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        self.count += 1;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn ppm_encoding() {
+        let grid = Grid::from_rows(vec![vec![true, false]]).unwrap();
+        let image = to_ppm(&grid, 2, |on| if *on { WHITE } else { BLACK });
+
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        for _ in 0..2 {
+            expected.extend_from_slice(&[255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]);
+        }
+        assert_eq!(expected, image);
+    }
+
+    #[test]
+    fn gradient_blends() {
+        assert_eq!(BLACK, gradient(BLACK, WHITE, 0.0));
+        assert_eq!([128, 128, 128], gradient(BLACK, WHITE, 0.5));
+        assert_eq!(WHITE, gradient(BLACK, WHITE, 2.0));
+    }
+
+    #[test]
+    fn frames_are_numbered() {
+        let dir = env::temp_dir().join(format!("aoc_2021_frames_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let grid = Grid::new(3, 2, 0);
+        let mut frames = Frames::create(&dir).unwrap();
+        frames.write(&grid, |_| BLACK).unwrap();
+        let path = frames.write(&grid, |_| WHITE).unwrap();
+
+        assert_eq!(2, frames.count());
+        assert_eq!(dir.join("frame_00001.ppm"), path);
+        assert!(fs::read(&path).unwrap().starts_with(b"P6\n399 266\n255\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}